@@ -0,0 +1,114 @@
+use std::{env, fs, path::Path, process};
+
+use advent_of_code_2022::{config::Config, day_04, leaderboard, registry, scaffold};
+
+const USAGE: &str = "usage:
+    aoc run --day <N> [--part <1|2>] [--input <path>] [--config <path>] [--set <key=value>]...
+    aoc new --day <N>
+    aoc leaderboard <export.json>
+    aoc camp [--input <path>]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = match args.as_slice() {
+        ["run", options @ ..] => run_day(options),
+        ["new", options @ ..] => new_day(options),
+        ["leaderboard", path] => run_leaderboard(path),
+        ["camp", options @ ..] => run_camp(options),
+        _ => Err(USAGE.to_string()),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
+
+// value following `--name`, if given
+fn option<'a>(options: &[&'a str], name: &str) -> Option<&'a str> {
+    let idx = options.iter().position(|option| *option == name)?;
+    options.get(idx + 1).copied()
+}
+
+// every value following `--name`
+fn all_options<'a>(options: &[&'a str], name: &str) -> Vec<&'a str> {
+    options
+        .windows(2)
+        .filter(|pair| pair[0] == name)
+        .map(|pair| pair[1])
+        .collect()
+}
+
+fn day_option(options: &[&str]) -> Result<u32, String> {
+    let day = option(options, "--day").ok_or(USAGE)?;
+    day.parse().map_err(|_| format!("invalid day: {day}"))
+}
+
+fn run_day(options: &[&str]) -> Result<(), String> {
+    let day = day_option(options)?;
+    let solution = registry::find(day).ok_or(format!("day {day} is not solved yet"))?;
+
+    let parts = match option(options, "--part") {
+        Some("1") => vec![1],
+        Some("2") => vec![2],
+        Some(part) => return Err(format!("invalid part: {part}")),
+        None => vec![1, 2],
+    };
+
+    let path = match option(options, "--input") {
+        Some(path) => path.to_string(),
+        None => format!("input/day_{day:02}.txt"),
+    };
+    let input = fs::read_to_string(&path).map_err(|err| format!("{path}: {err}"))?;
+
+    let mut config = match option(options, "--config") {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+            Config::parse(&text).map_err(|err| format!("{path}: {err}"))?
+        }
+        None => Config::default(),
+    };
+    for pair in all_options(options, "--set") {
+        config.set_pair(pair).map_err(|err| err.to_string())?;
+    }
+    registry::check_config(&config).map_err(|err| err.to_string())?;
+
+    for part in parts {
+        let answer = solution
+            .run(part, &input, &config)
+            .expect("part is 1 or 2")
+            .map_err(|err| format!("day {day} part {part}: {err}"))?;
+        println!("day {day} part {part}: {answer}");
+    }
+    Ok(())
+}
+
+fn new_day(options: &[&str]) -> Result<(), String> {
+    let day = day_option(options)?;
+    let written = scaffold::new_day(Path::new("."), day).map_err(|err| err.to_string())?;
+    for path in written {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn run_leaderboard(path: &str) -> Result<(), String> {
+    let input = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    let leaderboard = leaderboard::parse_leaderboard(&input).map_err(|err| err.to_string())?;
+
+    println!("{}", leaderboard::render_standings(&leaderboard));
+    print!("{}", leaderboard::render_deltas(&leaderboard));
+    Ok(())
+}
+
+fn run_camp(options: &[&str]) -> Result<(), String> {
+    let path = option(options, "--input").unwrap_or("input/day_04.txt");
+    let input = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+
+    let groups = day_04::parse_camp(&input).map_err(|err| format!("{path}: {err}"))?;
+    let coverage = day_04::camp_coverage(&groups);
+    print!("{}", day_04::render_coverage(&coverage));
+    Ok(())
+}
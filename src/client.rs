@@ -0,0 +1,634 @@
+use std::{
+    fmt, fs,
+    io::{self, Read, Write},
+    net::TcpStream,
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_YEAR: u32 = 2022;
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+const USER_AGENT: &str = "github.com/cLazyZombie/advent-of-code-2022";
+
+#[derive(Debug)]
+pub enum ClientError {
+    Io(io::Error),
+    InvalidUrl(String),
+    InvalidResponse(String),
+    Status(u16, String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Io(err) => write!(f, "io error: {err}"),
+            ClientError::InvalidUrl(url) => write!(f, "invalid base url: {url}"),
+            ClientError::InvalidResponse(reason) => write!(f, "invalid response: {reason}"),
+            ClientError::Status(status, body) => write!(f, "http status {status}: {body}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<io::Error> for ClientError {
+    fn from(err: io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    Correct,
+    Incorrect(Option<String>), // "too high" / "too low" hint if given
+    TooSoon(Option<String>),   // remaining wait time if given, e.g. "4m 38s"
+    AlreadySolved,
+    Unknown(String),
+}
+
+/// Talks to the Advent of Code site (or anything serving the same paths).
+///
+/// Inputs are cached as `day_NN.txt` under `cache_dir`, the same layout as `input/`,
+/// and every request waits until `min_interval` has passed since the previous one.
+pub struct Client {
+    base_url: String,
+    year: u32,
+    session: String,
+    cache_dir: PathBuf,
+    min_interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Client {
+    pub fn new(session: &str) -> Self {
+        Client {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            year: DEFAULT_YEAR,
+            session: session.trim().to_string(),
+            cache_dir: PathBuf::from("input"),
+            min_interval: DEFAULT_MIN_INTERVAL,
+            last_request: None,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_year(mut self, year: u32) -> Self {
+        self.year = year;
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn input_path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join(format!("day_{day:02}.txt"))
+    }
+
+    /// Returns the cached input if present, otherwise downloads and caches it.
+    /// An empty file, like the placeholder `aoc new` writes, isn't a cache hit.
+    pub fn fetch_input(&mut self, day: u32) -> Result<String, ClientError> {
+        let path = self.input_path(day);
+        if let Ok(input) = fs::read_to_string(&path) {
+            if !input.trim().is_empty() {
+                return Ok(input);
+            }
+        }
+
+        let path_and_query = format!("/{}/day/{day}/input", self.year);
+        let input = self.send("GET", &path_and_query, None)?;
+
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(&path, &input)?;
+        Ok(input)
+    }
+
+    pub fn submit_answer(
+        &mut self,
+        day: u32,
+        part: u32,
+        answer: &str,
+    ) -> Result<SubmitOutcome, ClientError> {
+        let path_and_query = format!("/{}/day/{day}/answer", self.year);
+        let form = format!("level={part}&answer={}", url_encode(answer.trim()));
+        let body = self.send("POST", &path_and_query, Some(&form))?;
+        Ok(parse_outcome(&body))
+    }
+
+    fn send(
+        &mut self,
+        method: &str,
+        path_and_query: &str,
+        form: Option<&str>,
+    ) -> Result<String, ClientError> {
+        self.wait_for_rate_limit();
+
+        let url = BaseUrl::parse(&self.base_url)?;
+        let raw = match url.scheme {
+            Scheme::Http => send_tcp(&url, method, path_and_query, &self.session, form),
+            Scheme::Https => send_curl(&url, method, path_and_query, &self.session, form),
+        };
+        self.last_request = Some(Instant::now());
+
+        let (status, body) = parse_response(&raw?)?;
+        if status != 200 {
+            return Err(ClientError::Status(status, body.trim().to_string()));
+        }
+        Ok(body)
+    }
+
+    fn wait_for_rate_limit(&self) {
+        if let Some(last) = self.last_request {
+            let elapsed = last.elapsed();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Scheme {
+    Http,
+    Https,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct BaseUrl {
+    scheme: Scheme,
+    host: String,
+    port: u16,
+    path: String, // prefix without trailing '/'
+}
+
+impl BaseUrl {
+    fn parse(url: &str) -> Result<Self, ClientError> {
+        let invalid = || ClientError::InvalidUrl(url.to_string());
+
+        let (scheme, rest) = if let Some(rest) = url.strip_prefix("http://") {
+            (Scheme::Http, rest)
+        } else if let Some(rest) = url.strip_prefix("https://") {
+            (Scheme::Https, rest)
+        } else {
+            return Err(invalid());
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => rest.split_at(idx),
+            None => (rest, ""),
+        };
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+            None => match scheme {
+                Scheme::Http => (authority, 80),
+                Scheme::Https => (authority, 443),
+            },
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(BaseUrl {
+            scheme,
+            host: host.to_string(),
+            port,
+            path: path.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn to_url(&self, path_and_query: &str) -> String {
+        let scheme = match self.scheme {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+        };
+        format!(
+            "{scheme}://{}:{}{}{path_and_query}",
+            self.host, self.port, self.path
+        )
+    }
+}
+
+fn send_tcp(
+    url: &BaseUrl,
+    method: &str,
+    path_and_query: &str,
+    session: &str,
+    form: Option<&str>,
+) -> Result<Vec<u8>, ClientError> {
+    let mut stream = TcpStream::connect((url.host.as_str(), url.port))?;
+
+    let mut request = format!(
+        "{method} {}{path_and_query} HTTP/1.1\r\n\
+         Host: {}\r\n\
+         User-Agent: {USER_AGENT}\r\n\
+         Cookie: session={session}\r\n\
+         Connection: close\r\n",
+        url.path, url.host
+    );
+    if let Some(form) = form {
+        request += "Content-Type: application/x-www-form-urlencoded\r\n";
+        request += &format!("Content-Length: {}\r\n\r\n{form}", form.len());
+    } else {
+        request += "\r\n";
+    }
+
+    stream.write_all(request.as_bytes())?;
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+    Ok(raw)
+}
+
+// std has no TLS, so https goes through the curl binary; `-i` keeps the response
+// in the same raw form `send_tcp` returns. The session cookie goes in through a
+// config on stdin (`-K -`) so it never shows up in the process list.
+fn send_curl(
+    url: &BaseUrl,
+    method: &str,
+    path_and_query: &str,
+    session: &str,
+    form: Option<&str>,
+) -> Result<Vec<u8>, ClientError> {
+    let mut command = Command::new("curl");
+    command
+        .args(["-s", "-i", "-X", method])
+        .args(["-A", USER_AGENT])
+        .args(["-K", "-"]);
+    if let Some(form) = form {
+        command.args(["--data", form]);
+    }
+    command.arg(url.to_url(path_and_query));
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // dropping stdin closes it, so curl sees the end of the config
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(curl_config(session).as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(ClientError::InvalidResponse(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(output.stdout)
+}
+
+// curl config file syntax, quoted so any session value stays one argument
+fn curl_config(session: &str) -> String {
+    let quoted = session.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("cookie = \"session={quoted}\"\n")
+}
+
+fn parse_response(raw: &[u8]) -> Result<(u16, String), ClientError> {
+    let Some(head_end) = find_bytes(raw, b"\r\n\r\n") else {
+        return Err(ClientError::InvalidResponse(
+            "missing header end".to_string(),
+        ));
+    };
+    let head = String::from_utf8_lossy(&raw[..head_end]);
+    let body = &raw[head_end + 4..];
+
+    let mut head_lines = head.lines();
+    let status_line = head_lines.next().unwrap_or_default();
+    let status = status_line
+        .split_ascii_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(|| ClientError::InvalidResponse(status_line.to_string()))?;
+
+    let chunked = head_lines.any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+
+    // chunks are counted in bytes, so decode before turning the body into text
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        body.to_vec()
+    };
+
+    Ok((status, String::from_utf8_lossy(&body).into_owned()))
+}
+
+fn decode_chunked(body: &[u8]) -> Result<Vec<u8>, ClientError> {
+    let mut result = Vec::new();
+    let mut rest = body;
+    loop {
+        let Some(line_end) = find_bytes(rest, b"\r\n") else {
+            return Err(ClientError::InvalidResponse("bad chunk header".to_string()));
+        };
+        let size_line = String::from_utf8_lossy(&rest[..line_end]);
+        let after = &rest[line_end + 2..];
+        let size_hex = size_line.split(';').next().unwrap().trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| ClientError::InvalidResponse(size_line.to_string()))?;
+        if size == 0 {
+            break;
+        }
+        if after.len() < size {
+            return Err(ClientError::InvalidResponse("truncated chunk".to_string()));
+        }
+        result.extend_from_slice(&after[..size]);
+        rest = after[size..]
+            .strip_prefix(b"\r\n")
+            .unwrap_or(&after[size..]);
+    }
+    Ok(result)
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn url_encode(s: &str) -> String {
+    let mut result = String::new();
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                result.push(b as char)
+            }
+            _ => result += &format!("%{b:02X}"),
+        }
+    }
+    result
+}
+
+fn parse_outcome(html: &str) -> SubmitOutcome {
+    let text = main_text(html);
+
+    if text.contains("That's the right answer") {
+        SubmitOutcome::Correct
+    } else if text.contains("That's not the right answer") {
+        let hint = ["too high", "too low"]
+            .into_iter()
+            .find(|hint| text.contains(hint))
+            .map(|hint| hint.to_string());
+        SubmitOutcome::Incorrect(hint)
+    } else if text.contains("You gave an answer too recently") {
+        let wait = text
+            .find("You have ")
+            .map(|idx| &text[idx + 9..])
+            .and_then(|rest| {
+                rest.find(" left to wait")
+                    .map(|end| rest[..end].to_string())
+            });
+        SubmitOutcome::TooSoon(wait)
+    } else if text.contains("You don't seem to be solving the right level") {
+        SubmitOutcome::AlreadySolved
+    } else {
+        SubmitOutcome::Unknown(text)
+    }
+}
+
+// text inside <main>..</main> with tags stripped
+fn main_text(html: &str) -> String {
+    let start = html.find("<main>").map(|idx| idx + 6).unwrap_or(0);
+    let end = html[start..]
+        .find("</main>")
+        .map(|idx| start + idx)
+        .unwrap_or(html.len());
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html[start..end].chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{net::TcpListener, sync::mpsc};
+
+    // serves `responses` in order and reports each raw request back
+    fn mock_server(responses: Vec<String>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let request = read_request(&mut stream);
+                tx.send(request).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (format!("http://{addr}"), rx)
+    }
+
+    fn read_request(stream: &mut TcpStream) -> String {
+        let mut raw = Vec::new();
+        let mut buf = [0_u8; 1024];
+        loop {
+            let n = stream.read(&mut buf).unwrap();
+            raw.extend_from_slice(&buf[..n]);
+
+            let text = String::from_utf8_lossy(&raw).to_string();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let content_length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Content-Length: "))
+                    .map(|len| len.parse::<usize>().unwrap())
+                    .unwrap_or(0);
+                if body.len() >= content_length {
+                    return text;
+                }
+            }
+            if n == 0 {
+                return text;
+            }
+        }
+    }
+
+    fn ok_response(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc_client_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_parse_base_url() {
+        let url = BaseUrl::parse("http://127.0.0.1:8080/mock/").unwrap();
+        assert_eq!(url.scheme, Scheme::Http);
+        assert_eq!(url.host, "127.0.0.1");
+        assert_eq!(url.port, 8080);
+        assert_eq!(url.path, "/mock");
+
+        let url = BaseUrl::parse(DEFAULT_BASE_URL).unwrap();
+        assert_eq!(url.scheme, Scheme::Https);
+        assert_eq!(url.port, 443);
+        assert_eq!(
+            url.to_url("/2022/day/1/input"),
+            "https://adventofcode.com:443/2022/day/1/input"
+        );
+
+        assert!(BaseUrl::parse("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_response_chunked() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n1000\r\n3\r\n\n20\r\n0\r\n\r\n";
+        assert_eq!(parse_response(raw).unwrap(), (200, "1000\n20".to_string()));
+
+        // a chunk boundary inside the two bytes of `é`
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nd\xc3\r\n1\r\n\xa9\r\n0\r\n\r\n";
+        assert_eq!(parse_response(raw).unwrap(), (200, "d\u{e9}".to_string()));
+    }
+
+    #[test]
+    fn test_curl_config() {
+        assert_eq!(curl_config("53616c74"), "cookie = \"session=53616c74\"\n");
+        assert_eq!(
+            curl_config(r#"a"b\c"#),
+            "cookie = \"session=a\\\"b\\\\c\"\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_outcome() {
+        let html = "<html><main><article><p>That's the right answer! You are one gold star closer.</p></article></main></html>";
+        assert_eq!(parse_outcome(html), SubmitOutcome::Correct);
+
+        let html = "<main><article><p>That's not the right answer; your answer is too high.  Please wait one minute.</p></article></main>";
+        assert_eq!(
+            parse_outcome(html),
+            SubmitOutcome::Incorrect(Some("too high".to_string()))
+        );
+
+        let html = "<main><article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 38s left to wait. <a href=\"/2022/day/1\">[Return to Day 1]</a></p></article></main>";
+        assert_eq!(
+            parse_outcome(html),
+            SubmitOutcome::TooSoon(Some("4m 38s".to_string()))
+        );
+
+        let html = "<main><article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article></main>";
+        assert_eq!(parse_outcome(html), SubmitOutcome::AlreadySolved);
+    }
+
+    #[test]
+    fn test_fetch_input_caches() {
+        let (base_url, requests) = mock_server(vec![ok_response("1000\n2000\n")]);
+        let cache_dir = temp_cache_dir("fetch");
+        let mut client = Client::new("secret")
+            .with_base_url(&base_url)
+            .with_cache_dir(&cache_dir)
+            .with_min_interval(Duration::ZERO);
+
+        assert_eq!(client.fetch_input(1).unwrap(), "1000\n2000\n");
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /2022/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=secret\r\n"));
+
+        // second fetch is served from disk, the mock only answers once
+        assert_eq!(client.fetch_input(1).unwrap(), "1000\n2000\n");
+        assert_eq!(
+            fs::read_to_string(cache_dir.join("day_01.txt")).unwrap(),
+            "1000\n2000\n"
+        );
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_input_replaces_placeholder() {
+        let (base_url, requests) = mock_server(vec![ok_response("1000\n")]);
+        let cache_dir = temp_cache_dir("placeholder");
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::write(cache_dir.join("day_02.txt"), "").unwrap();
+        let mut client = Client::new("secret")
+            .with_base_url(&base_url)
+            .with_cache_dir(&cache_dir)
+            .with_min_interval(Duration::ZERO);
+
+        assert_eq!(client.fetch_input(2).unwrap(), "1000\n");
+        assert!(requests.recv().is_ok());
+        assert_eq!(
+            fs::read_to_string(cache_dir.join("day_02.txt")).unwrap(),
+            "1000\n"
+        );
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_submit_answer() {
+        let body = "<main><article><p>That's the right answer!</p></article></main>";
+        let (base_url, requests) = mock_server(vec![ok_response(body)]);
+        let mut client = Client::new("secret")
+            .with_base_url(&base_url)
+            .with_min_interval(Duration::ZERO);
+
+        let outcome = client.submit_answer(6, 2, "3837").unwrap();
+        assert_eq!(outcome, SubmitOutcome::Correct);
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2022/day/6/answer HTTP/1.1\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=3837"));
+    }
+
+    #[test]
+    fn test_error_status() {
+        let response =
+            "HTTP/1.1 400 Bad Request\r\nContent-Length: 9\r\n\r\nno input\n".to_string();
+        let (base_url, _requests) = mock_server(vec![response]);
+        let mut client = Client::new("bad")
+            .with_base_url(&base_url)
+            .with_cache_dir(temp_cache_dir("error"))
+            .with_min_interval(Duration::ZERO);
+
+        let err = client.fetch_input(3).unwrap_err();
+        assert!(matches!(err, ClientError::Status(400, body) if body == "no input"));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let body = "<main><p>That's the right answer!</p></main>";
+        let (base_url, _requests) = mock_server(vec![ok_response(body), ok_response(body)]);
+        let mut client = Client::new("secret")
+            .with_base_url(&base_url)
+            .with_min_interval(Duration::from_millis(200));
+
+        let start = Instant::now();
+        client.submit_answer(1, 1, "1").unwrap();
+        client.submit_answer(1, 2, "2").unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,215 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Syntax(usize, String), // 1-based line number (0 for overrides), offending line
+    InvalidValue(String, String),
+    UnknownKey(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Syntax(0, text) => write!(f, "expected `key=value`, got `{text}`"),
+            ConfigError::Syntax(line, text) => {
+                write!(f, "line {line}: expected `key = value`, got `{text}`")
+            }
+            ConfigError::InvalidValue(key, value) => {
+                write!(f, "invalid value for {key}: `{value}`")
+            }
+            ConfigError::UnknownKey(key) => write!(f, "unknown key {key}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Flat `key = value` settings, keys are `day_NN.name`, e.g.
+///
+/// ```text
+/// # what-if: bigger disk
+/// day_07.total_space = 80000000
+/// day_10.sample_cycles = 20, 60, 100
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    values: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn parse(input: &str) -> Result<Self, ConfigError> {
+        let mut config = Config::default();
+        for (idx, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            config
+                .set_pair(line)
+                .map_err(|_| ConfigError::Syntax(idx + 1, line.to_string()))?;
+        }
+        Ok(config)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.values
+            .insert(key.trim().to_string(), value.trim().to_string());
+    }
+
+    /// Applies a `key=value` override, as passed to the runner's `--set`.
+    pub fn set_pair(&mut self, pair: &str) -> Result<(), ConfigError> {
+        match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                self.set(key, value);
+                Ok(())
+            }
+            _ => Err(ConfigError::Syntax(0, pair.to_string())),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    pub fn get_raw(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// The parsed value of `key`, or `default` if it isn't set.
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, ConfigError> {
+        match self.get_raw(key) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue(key.to_string(), value.to_string())),
+            None => Ok(default),
+        }
+    }
+
+    /// Like `get_or`, rejecting values `valid` returns false for.
+    pub fn get_valid_or<T: FromStr>(
+        &self,
+        key: &str,
+        default: T,
+        valid: impl Fn(&T) -> bool,
+    ) -> Result<T, ConfigError> {
+        let value = self.get_or(key, default)?;
+        if valid(&value) {
+            Ok(value)
+        } else {
+            Err(self.invalid_value(key))
+        }
+    }
+
+    /// Like `get_valid_or` for lists, every item has to be valid.
+    pub fn get_valid_list_or<T: FromStr>(
+        &self,
+        key: &str,
+        default: Vec<T>,
+        valid: impl Fn(&T) -> bool,
+    ) -> Result<Vec<T>, ConfigError> {
+        let values = self.get_list_or(key, default)?;
+        if values.iter().all(valid) {
+            Ok(values)
+        } else {
+            Err(self.invalid_value(key))
+        }
+    }
+
+    /// `InvalidValue` for whatever `key` is set to, for checks that need
+    /// more than the value itself.
+    pub fn invalid_value(&self, key: &str) -> ConfigError {
+        let value = self.get_raw(key).unwrap_or_default();
+        ConfigError::InvalidValue(key.to_string(), value.to_string())
+    }
+
+    /// Like `get_or` for comma-separated lists.
+    pub fn get_list_or<T: FromStr>(
+        &self,
+        key: &str,
+        default: Vec<T>,
+    ) -> Result<Vec<T>, ConfigError> {
+        match self.get_raw(key) {
+            Some(value) => value
+                .split(',')
+                .map(|item| item.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| ConfigError::InvalidValue(key.to_string(), value.to_string())),
+            None => Ok(default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_CONFIG: &str = r#"
+# what-if run
+day_07.total_space = 80000000
+day_10.sample_cycles = 20, 60,100
+
+day_16.start=BB
+"#;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(SAMPLE_CONFIG).unwrap();
+        assert_eq!(config.get_or("day_07.total_space", 0_u32), Ok(80000000));
+        assert_eq!(
+            config.get_or("day_07.need_space", 30000000_u32),
+            Ok(30000000)
+        );
+        assert_eq!(
+            config.get_list_or("day_10.sample_cycles", vec![]),
+            Ok(vec![20, 60, 100])
+        );
+        assert_eq!(config.get_raw("day_16.start"), Some("BB"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Config::parse("day_07.total_space = 1\nnonsense"),
+            Err(ConfigError::Syntax(2, "nonsense".to_string()))
+        );
+
+        let config = Config::parse("day_11.rounds = many").unwrap();
+        assert_eq!(
+            config.get_or("day_11.rounds", 20_u32),
+            Err(ConfigError::InvalidValue(
+                "day_11.rounds".to_string(),
+                "many".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_set_pair() {
+        let mut config = Config::parse(SAMPLE_CONFIG).unwrap();
+        config.set_pair("day_07.total_space=90000000").unwrap();
+        assert_eq!(config.get_or("day_07.total_space", 0_u32), Ok(90000000));
+        assert!(config.set_pair("=1").is_err());
+    }
+
+    #[test]
+    fn test_get_valid() {
+        let config = Config::parse("day_11.relief = 0\nday_10.sample_cycles = 20, -1").unwrap();
+        assert_eq!(
+            config.get_valid_or("day_11.relief", 3_u64, |&relief| relief > 0),
+            Err(ConfigError::InvalidValue(
+                "day_11.relief".to_string(),
+                "0".to_string()
+            ))
+        );
+        assert_eq!(
+            config.get_valid_or("day_11.part1_rounds", 20_u32, |_| false),
+            Err(ConfigError::InvalidValue(
+                "day_11.part1_rounds".to_string(),
+                String::new()
+            ))
+        );
+        assert!(config
+            .get_valid_list_or("day_10.sample_cycles", vec![], |&cycle: &i32| cycle > 0)
+            .is_err());
+    }
+}
@@ -1,44 +1,579 @@
-pub fn most_calories(input: &str) -> u32 {
-    let mut max_calories = 0;
-    let mut sum_calories = 0;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fmt,
+    io::{self, BufRead},
+    str::FromStr,
+};
 
-    for line in input.lines() {
-        let Ok(calories) = line.parse::<u32>() else {
-            if sum_calories > max_calories {
-                max_calories = sum_calories;
+pub fn most_calories(input: &str) -> u64 {
+    try_most_calories(input).unwrap()
+}
+
+pub fn top_three_calories(input: &str) -> u64 {
+    try_top_three_calories(input).unwrap()
+}
+
+pub fn try_most_calories(input: &str) -> Result<u64, ParseError> {
+    let inventory: ElfInventory = input.parse()?;
+    Ok(inventory.top_n(1).iter().map(|elf| elf.total()).sum())
+}
+
+pub fn try_top_three_calories(input: &str) -> Result<u64, ParseError> {
+    let inventory: ElfInventory = input.parse()?;
+    Ok(inventory.top_n(3).iter().map(|elf| elf.total()).sum())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize, // 1-based
+    pub text: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: not a calorie count: `{}`",
+            self.line, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    pub index: usize, // position in the input, 0-based
+    pub items: Vec<u32>,
+}
+
+impl Elf {
+    /// Wider than the items, so many large ones can't overflow.
+    pub fn total(&self) -> u64 {
+        self.items.iter().copied().map(u64::from).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfInventory {
+    elves: Vec<Elf>,
+}
+
+impl FromStr for ElfInventory {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut elves = Vec::new();
+        let mut items = Vec::new();
+
+        for (idx, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                if !items.is_empty() {
+                    let index = elves.len();
+                    elves.push(Elf {
+                        index,
+                        items: std::mem::take(&mut items),
+                    });
+                }
+                continue;
             }
-            sum_calories = 0;
-            continue;
-        };
 
-        sum_calories += calories;
+            let calories = line.parse::<u32>().map_err(|_| ParseError {
+                line: idx + 1,
+                text: line.to_string(),
+            })?;
+            items.push(calories);
+        }
+
+        // the last elf has no blank line after it
+        if !items.is_empty() {
+            let index = elves.len();
+            elves.push(Elf { index, items });
+        }
+
+        Ok(ElfInventory { elves })
+    }
+}
+
+impl ElfInventory {
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+
+    /// The `n` elves carrying the most calories, most first. Ties go to the
+    /// elf that comes first in the input.
+    pub fn top_n(&self, n: usize) -> Vec<&Elf> {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        // min-heap of the best n seen so far, the root is the one to evict
+        let mut heap = BinaryHeap::with_capacity(n + 1);
+        for elf in &self.elves {
+            heap.push(Reverse((elf.total(), Reverse(elf.index))));
+            if heap.len() > n {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((_, Reverse(index)))| &self.elves[index])
+            .collect()
+    }
+
+    /// 1-based rank of the elf at `index` by total calories, ties share a rank.
+    pub fn rank(&self, index: usize) -> Option<usize> {
+        let total = self.elves.get(index)?.total();
+        let higher = self.elves.iter().filter(|elf| elf.total() > total).count();
+        Some(higher + 1)
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+    ZeroBucketWidth,
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(err) => write!(f, "io error: {err}"),
+            StreamError::Parse(err) => write!(f, "{err}"),
+            StreamError::ZeroBucketWidth => write!(f, "histogram bucket width must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> Self {
+        StreamError::Io(err)
     }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(err: ParseError) -> Self {
+        StreamError::Parse(err)
+    }
+}
 
-    max_calories
+/// Yields `(elf index, total calories)` per elf while reading one line at a
+/// time, so memory does not grow with the input.
+pub struct ElfStream<R> {
+    reader: R,
+    line: String,
+    line_no: usize,
+    next_index: usize,
+    done: bool,
 }
 
-pub fn top_three_calories(input: &str) -> u32 {
-    let mut top_three: [u32; 4] = [0, 0, 0, 0]; // 1st element is new input
-    let mut sum_calories = 0;
+pub fn stream_elves<R: BufRead>(reader: R) -> ElfStream<R> {
+    ElfStream {
+        reader,
+        line: String::new(),
+        line_no: 0,
+        next_index: 0,
+        done: false,
+    }
+}
+
+impl<R: BufRead> ElfStream<R> {
+    fn next_elf(&mut self) -> Result<Option<(usize, u64)>, StreamError> {
+        let mut total: Option<u64> = None;
+        while !self.done {
+            self.line.clear();
+            if self.reader.read_line(&mut self.line)? == 0 {
+                self.done = true;
+                break;
+            }
+            self.line_no += 1;
+
+            let line = self.line.trim();
+            if line.is_empty() {
+                if total.is_some() {
+                    break;
+                }
+                continue;
+            }
+
+            let calories = line.parse::<u64>().map_err(|_| ParseError {
+                line: self.line_no,
+                text: line.to_string(),
+            })?;
+            *total.get_or_insert(0) += calories;
+        }
+
+        Ok(total.map(|total| {
+            let index = self.next_index;
+            self.next_index += 1;
+            (index, total)
+        }))
+    }
+}
+
+impl<R: BufRead> Iterator for ElfStream<R> {
+    type Item = Result<(usize, u64), StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_elf() {
+            Ok(elf) => elf.map(Ok),
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+// keeps the n largest (total, index) pairs, ties go to the earlier elf
+struct TopN {
+    n: usize,
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopN {
+    fn new(n: usize) -> Self {
+        TopN {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    fn push(&mut self, index: usize, total: u64) {
+        if self.n == 0 {
+            return;
+        }
+        self.heap.push(Reverse((total, Reverse(index))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
 
-    for line in input.lines() {
-        let Ok(calories) = line.parse::<u32>() else {
-            top_three[0] = sum_calories;
-            top_three.sort();
-            sum_calories = 0;
-            continue;
+    fn into_sorted(self) -> Vec<(usize, u64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| (index, total))
+            .collect()
+    }
+}
+
+/// `(elf index, total)` of the `n` elves with the most calories, most first.
+pub fn stream_top_n<R: BufRead>(reader: R, n: usize) -> Result<Vec<(usize, u64)>, StreamError> {
+    let mut top = TopN::new(n);
+    for elf in stream_elves(reader) {
+        let (index, total) = elf?;
+        top.push(index, total);
+    }
+    Ok(top.into_sorted())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub elf_count: usize,
+    pub total_calories: u64,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64, // P² estimate, exact for up to five elves
+    pub top: Vec<(usize, u64)>,
+    pub histogram: Vec<(u64, usize)>, // (bucket lower bound, elf count), empty buckets left out
+}
+
+/// Everything in `Summary` from a single pass over `reader`.
+pub fn stream_summary<R: BufRead>(
+    reader: R,
+    top_n: usize,
+    bucket_width: u64,
+) -> Result<Summary, StreamError> {
+    if bucket_width == 0 {
+        return Err(StreamError::ZeroBucketWidth);
+    }
+
+    let mut elf_count = 0;
+    let mut total_calories = 0;
+    let mut min = u64::MAX;
+    let mut max = 0;
+    let mut median = P2Quantile::new(0.5);
+    let mut top = TopN::new(top_n);
+    let mut histogram = BTreeMap::new();
+
+    for elf in stream_elves(reader) {
+        let (index, total) = elf?;
+        elf_count += 1;
+        total_calories += total;
+        min = min.min(total);
+        max = max.max(total);
+        median.push(total as f64);
+        top.push(index, total);
+        *histogram
+            .entry(total / bucket_width * bucket_width)
+            .or_insert(0) += 1;
+    }
+
+    Ok(Summary {
+        elf_count,
+        total_calories,
+        min: if elf_count == 0 { 0 } else { min },
+        max,
+        mean: if elf_count == 0 {
+            0.0
+        } else {
+            total_calories as f64 / elf_count as f64
+        },
+        median: median.estimate(),
+        top: top.into_sorted(),
+        histogram: histogram.into_iter().collect(),
+    })
+}
+
+/// Jain & Chlamtac's P² estimator: tracks a quantile with five markers whose
+/// heights are nudged along a parabola as observations arrive.
+struct P2Quantile {
+    p: f64,
+    count: usize,
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    fn new(p: f64) -> Self {
+        P2Quantile {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    fn push(&mut self, x: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        let h = &mut self.heights;
+        let k = if x < h[0] {
+            h[0] = x;
+            0
+        } else if x >= h[4] {
+            h[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| x < h[i + 1]).unwrap()
         };
 
-        sum_calories += calories;
+        for i in k + 1..5 {
+            self.positions[i] += 1.0;
+        }
+        for i in 0..5 {
+            self.desired[i] += self.increments[i];
+        }
+
+        for i in 1..4 {
+            let n = self.positions;
+            let d = self.desired[i] - n[i];
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = d.signum();
+                let q = &mut self.heights;
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                self.positions[i] += d;
+            }
+        }
     }
 
-    top_three.iter().skip(1).sum()
+    fn estimate(&self) -> f64 {
+        if self.count >= 5 {
+            return self.heights[2];
+        }
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        // exact quantile of the few values seen so far
+        let mut seen = self.heights[..self.count].to_vec();
+        seen.sort_by(f64::total_cmp);
+        let rank = self.p * (self.count - 1) as f64;
+        let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+        seen[lo] + (seen[hi] - seen[lo]) * (rank - lo as f64)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const SAMPLE_INPUT: &str = r#"1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000"#;
+
+    #[test]
+    fn test_parse_sample() {
+        let inventory: ElfInventory = SAMPLE_INPUT.parse().unwrap();
+        let elves = inventory.elves();
+        assert_eq!(elves.len(), 5);
+        assert_eq!(elves[0].items, [1000, 2000, 3000]);
+        assert_eq!(elves[3].total(), 24000);
+        // no trailing blank line, the last elf still counts
+        assert_eq!(elves[4].total(), 10000);
+    }
+
+    #[test]
+    fn test_top_n() {
+        let inventory: ElfInventory = SAMPLE_INPUT.parse().unwrap();
+        let top: Vec<_> = inventory.top_n(3).iter().map(|elf| elf.index).collect();
+        assert_eq!(top, [3, 2, 4]);
+        assert_eq!(inventory.top_n(10).len(), 5);
+        assert!(inventory.top_n(0).is_empty());
+    }
+
+    #[test]
+    fn test_rank() {
+        let inventory: ElfInventory = SAMPLE_INPUT.parse().unwrap();
+        assert_eq!(inventory.rank(3), Some(1));
+        assert_eq!(inventory.rank(1), Some(5));
+        assert_eq!(inventory.rank(5), None);
+    }
+
+    #[test]
+    fn test_last_elf_is_counted() {
+        assert_eq!(most_calories("1\n\n2\n3"), 5);
+        assert_eq!(top_three_calories("1\n\n2\n\n3\n\n4"), 9);
+    }
+
+    #[test]
+    fn test_large_totals() {
+        let input = format!("{0}\n{0}\n\n{0}", u32::MAX);
+        assert_eq!(most_calories(&input), 2 * u64::from(u32::MAX));
+        assert_eq!(top_three_calories(&input), 3 * u64::from(u32::MAX));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = "1000\n\n20OO\n".parse::<ElfInventory>().unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                line: 3,
+                text: "20OO".to_string()
+            }
+        );
+
+        let err = ParseError {
+            line: 2,
+            text: "lots".to_string(),
+        };
+        assert_eq!(try_most_calories("1\nlots\n"), Err(err.clone()));
+        assert_eq!(try_top_three_calories("1\nlots\n"), Err(err));
+        assert!(matches!(
+            stream_summary(SAMPLE_INPUT.as_bytes(), 1, 0),
+            Err(StreamError::ZeroBucketWidth)
+        ));
+    }
+
+    #[test]
+    fn test_stream_elves() {
+        let elves: Vec<_> = stream_elves(SAMPLE_INPUT.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            elves,
+            [(0, 6000), (1, 4000), (2, 11000), (3, 24000), (4, 10000)]
+        );
+
+        let mut elves = stream_elves("1\n\nfour\n".as_bytes());
+        assert_eq!(elves.next().unwrap().unwrap(), (0, 1));
+        assert!(matches!(
+            elves.next(),
+            Some(Err(StreamError::Parse(ParseError { line: 3, .. })))
+        ));
+        assert!(elves.next().is_none());
+    }
+
+    #[test]
+    fn test_stream_top_n() {
+        let top = stream_top_n(SAMPLE_INPUT.as_bytes(), 3).unwrap();
+        assert_eq!(top, [(3, 24000), (2, 11000), (4, 10000)]);
+
+        let input = include_str!("../input/day_01.txt");
+        let top = stream_top_n(input.as_bytes(), 3).unwrap();
+        assert_eq!(top.iter().map(|(_, total)| total).sum::<u64>(), 212836);
+    }
+
+    #[test]
+    fn test_stream_summary_sample() {
+        let summary = stream_summary(SAMPLE_INPUT.as_bytes(), 1, 5000).unwrap();
+        assert_eq!(summary.elf_count, 5);
+        assert_eq!(summary.total_calories, 55000);
+        assert_eq!(summary.min, 4000);
+        assert_eq!(summary.max, 24000);
+        assert_eq!(summary.mean, 11000.0);
+        assert_eq!(summary.median, 10000.0);
+        assert_eq!(summary.top, [(3, 24000)]);
+        assert_eq!(
+            summary.histogram,
+            [(0, 1), (5000, 1), (10000, 2), (20000, 1)]
+        );
+    }
+
+    #[test]
+    fn test_stream_summary() {
+        let input = include_str!("../input/day_01.txt");
+        let summary = stream_summary(input.as_bytes(), 3, 10000).unwrap();
+
+        let inventory: ElfInventory = input.parse().unwrap();
+        let mut totals: Vec<u64> = inventory.elves().iter().map(Elf::total).collect();
+        totals.sort();
+        let median = totals[totals.len() / 2] as f64;
+
+        assert_eq!(summary.elf_count, inventory.elves().len());
+        assert_eq!(summary.max, 74394);
+        assert!((summary.median - median).abs() / median < 0.05);
+        assert_eq!(
+            summary
+                .histogram
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<usize>(),
+            summary.elf_count
+        );
+    }
+
     #[test]
     fn part_1() {
         let input = include_str!("../input/day_01.txt");
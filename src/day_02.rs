@@ -1,110 +1,671 @@
-#[derive(Debug, Copy, Clone)]
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Index of a shape within its `Game`.
+pub type Shape = usize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum RPS {
     Rock,
     Paper,
     Scissors,
 }
 
-impl From<&str> for RPS {
-    fn from(s: &str) -> Self {
-        match s {
-            "A" | "X" => RPS::Rock,
-            "B" | "Y" => RPS::Paper,
-            "C" | "Z" => RPS::Scissors,
-            _ => panic!("Invalid input"),
-        }
+impl RPS {
+    fn game() -> &'static Game {
+        static GAME: OnceLock<Game> = OnceLock::new();
+        GAME.get_or_init(Game::rock_paper_scissors)
     }
-}
 
-impl RPS {
     pub fn shape_score(&self) -> u32 {
-        match self {
-            RPS::Rock => 1,
-            RPS::Paper => 2,
-            RPS::Scissors => 3,
-        }
+        RPS::game().shape_score(*self as Shape)
     }
 
     pub fn beats_score(&self, other: RPS) -> u32 {
-        match (*self, other) {
-            (RPS::Rock, RPS::Rock) => 3,
-            (RPS::Paper, RPS::Paper) => 3,
-            (RPS::Scissors, RPS::Scissors) => 3,
-            (RPS::Rock, RPS::Scissors) => 6,
-            (RPS::Paper, RPS::Rock) => 6,
-            (RPS::Scissors, RPS::Paper) => 6,
-            _ => 0,
-        }
+        let game = RPS::game();
+        game.outcome_score(game.outcome(*self as Shape, other as Shape))
     }
 }
 
-fn score(mine: RPS, other: RPS) -> u32 {
+pub fn score(mine: RPS, other: RPS) -> u32 {
     mine.shape_score() + mine.beats_score(other)
 }
 
-#[derive(Debug, Copy, Clone)]
-enum MatchResult {
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MatchResult {
     Win,
     Lose,
     Draw,
 }
 
-impl From<&str> for MatchResult {
-    fn from(s: &str) -> Self {
-        match s {
-            "X" => MatchResult::Lose,
-            "Y" => MatchResult::Draw,
-            "Z" => MatchResult::Win,
-            _ => panic!("Invalid input"),
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dominance {
+    /// `a` beats `b` when `(a - b) mod n` is odd. Needs an odd number of shapes
+    /// listed in cycle order; every shape then beats exactly half of the
+    /// others.
+    Cyclic,
+    /// `beats[a][b]` is true when `a` beats `b`; neither beating the other is
+    /// a draw.
+    Table(Vec<Vec<bool>>),
+}
+
+/// What the second column of the strategy guide means.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Strategy {
+    Shape,
+    Outcome,
+}
+
+/// One way of reading the guide's second column: the `i`-th letter means
+/// shape `meanings[i]`, or for `Strategy::Outcome` the `meanings[i]`-th of
+/// lose/draw/win.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoding {
+    pub strategy: Strategy,
+    pub meanings: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodingReport {
+    pub decoding: Decoding,
+    pub total: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: Shape,
+    pub mine: Shape,
+    pub result: MatchResult,
+    pub shape_score: u32,
+    pub outcome_score: u32,
+}
+
+impl Round {
+    pub fn score(&self) -> u32 {
+        self.shape_score + self.outcome_score
+    }
+}
+
+/// A symmetric equilibrium: if both players mix like `mix`, neither can raise
+/// their expected per-round score (`value`) by deviating.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equilibrium {
+    pub mix: Vec<f64>,
+    pub value: f64,
+}
+
+const EPSILON: f64 = 1e-9;
+
+const RESULTS: [MatchResult; 3] = [MatchResult::Lose, MatchResult::Draw, MatchResult::Win];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize, // 1-based
+    pub text: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: invalid round `{}`", self.line, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Why `Game::new` refused a set of shapes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    /// A game needs between 1 and 26 shapes.
+    ShapeCount(usize),
+    /// The dominance table is not `n` by `n`.
+    TableSize(usize),
+    /// Two shapes beat each other.
+    MutualWin(String, String),
+    /// A cyclic game with an even number of shapes: opposite shapes would
+    /// beat each other.
+    EvenCycle(usize),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::ShapeCount(count) => {
+                write!(f, "a game needs between 1 and 26 shapes, got {count}")
+            }
+            GameError::TableSize(count) => {
+                write!(f, "dominance table must be {count}x{count}")
+            }
+            GameError::MutualWin(a, b) => write!(f, "{a} and {b} beat each other"),
+            GameError::EvenCycle(count) => {
+                write!(
+                    f,
+                    "a cyclic game needs an odd number of shapes, got {count}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+/// A hand game of N shapes: who beats whom, how shapes and outcomes score and
+/// which letters the strategy guide uses for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    names: Vec<String>,
+    dominance: Dominance,
+    shape_scores: Vec<u32>,
+    outcome_scores: [u32; 3], // lose, draw, win
+    opponent_letters: Vec<String>,
+    response_letters: Vec<String>,
+    outcome_letters: [String; 3], // lose, draw, win
+}
+
+impl Game {
+    /// Shapes score 1, 2, 3, ... in order and outcomes 0/3/6. The opponent
+    /// plays `A`, `B`, ... and the response letters end at `Z`, which gives the
+    /// puzzle's `X`/`Y`/`Z` for three shapes.
+    pub fn new(names: &[&str], dominance: Dominance) -> Result<Self, GameError> {
+        let count = names.len();
+        if !(1..=26).contains(&count) {
+            return Err(GameError::ShapeCount(count));
+        }
+        match &dominance {
+            Dominance::Cyclic if count.is_multiple_of(2) => {
+                return Err(GameError::EvenCycle(count))
+            }
+            Dominance::Cyclic => {}
+            Dominance::Table(beats) => {
+                if beats.len() != count || beats.iter().any(|row| row.len() != count) {
+                    return Err(GameError::TableSize(count));
+                }
+                for a in 0..count {
+                    for b in 0..count {
+                        if beats[a][b] && beats[b][a] {
+                            return Err(GameError::MutualWin(
+                                names[a].to_string(),
+                                names[b].to_string(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        let letters = |first: u8| (0..count).map(move |i| char::from(first + i as u8).to_string());
+        Ok(Game {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            dominance,
+            shape_scores: (1..=count as u32).collect(),
+            outcome_scores: [0, 3, 6],
+            opponent_letters: letters(b'A').collect(),
+            response_letters: letters(b'Z' + 1 - count as u8).collect(),
+            outcome_letters: ["X".to_string(), "Y".to_string(), "Z".to_string()],
+        })
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Game::new(&["Rock", "Paper", "Scissors"], Dominance::Cyclic)
+            .expect("three shapes make an odd cycle")
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Game::new(
+            &["Rock", "Paper", "Scissors", "Spock", "Lizard"],
+            Dominance::Cyclic,
+        )
+        .expect("five shapes make an odd cycle")
+    }
+
+    pub fn with_shape_scores(mut self, scores: &[u32]) -> Self {
+        assert_eq!(scores.len(), self.names.len(), "one score per shape");
+        self.shape_scores = scores.to_vec();
+        self
+    }
+
+    /// Scores for a loss, a draw and a win.
+    pub fn with_outcome_scores(mut self, lose: u32, draw: u32, win: u32) -> Self {
+        self.outcome_scores = [lose, draw, win];
+        self
+    }
+
+    pub fn with_opponent_letters(mut self, letters: &[&str]) -> Self {
+        assert_eq!(letters.len(), self.names.len(), "one letter per shape");
+        self.opponent_letters = letters.iter().map(|letter| letter.to_string()).collect();
+        self
+    }
+
+    pub fn with_response_letters(mut self, letters: &[&str]) -> Self {
+        assert_eq!(letters.len(), self.names.len(), "one letter per shape");
+        self.response_letters = letters.iter().map(|letter| letter.to_string()).collect();
+        self
+    }
+
+    /// Letters for "lose", "draw" and "win" when the guide gives outcomes.
+    pub fn with_outcome_letters(mut self, lose: &str, draw: &str, win: &str) -> Self {
+        self.outcome_letters = [lose.to_string(), draw.to_string(), win.to_string()];
+        self
+    }
+
+    pub fn shape_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.names[shape]
+    }
+
+    pub fn shape(&self, name: &str) -> Option<Shape> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn beats(&self, a: Shape, b: Shape) -> bool {
+        match &self.dominance {
+            Dominance::Cyclic => {
+                let n = self.names.len();
+                (a + n - b) % n % 2 == 1
+            }
+            Dominance::Table(beats) => beats[a][b],
         }
     }
+
+    pub fn outcome(&self, mine: Shape, other: Shape) -> MatchResult {
+        if self.beats(mine, other) {
+            MatchResult::Win
+        } else if self.beats(other, mine) {
+            MatchResult::Lose
+        } else {
+            MatchResult::Draw
+        }
+    }
+
+    pub fn shape_score(&self, shape: Shape) -> u32 {
+        self.shape_scores[shape]
+    }
+
+    pub fn outcome_score(&self, result: MatchResult) -> u32 {
+        match result {
+            MatchResult::Lose => self.outcome_scores[0],
+            MatchResult::Draw => self.outcome_scores[1],
+            MatchResult::Win => self.outcome_scores[2],
+        }
+    }
+
+    pub fn score(&self, mine: Shape, other: Shape) -> u32 {
+        self.shape_score(mine) + self.outcome_score(self.outcome(mine, other))
+    }
+
+    /// The shape to play against `other` to get `result`. When several shapes
+    /// do, the one with the highest shape score (then the first) is chosen.
+    pub fn choose(&self, other: Shape, result: MatchResult) -> Option<Shape> {
+        (0..self.shape_count())
+            .filter(|&mine| self.outcome(mine, other) == result)
+            .fold(None, |best: Option<Shape>, mine| match best {
+                Some(best) if self.shape_score(best) >= self.shape_score(mine) => Some(best),
+                _ => Some(mine),
+            })
+    }
+
+    pub fn parse_opponent(&self, letter: &str) -> Option<Shape> {
+        self.opponent_letters.iter().position(|l| l == letter)
+    }
+
+    pub fn parse_response(&self, letter: &str) -> Option<Shape> {
+        self.response_letters.iter().position(|l| l == letter)
+    }
+
+    pub fn parse_outcome(&self, letter: &str) -> Option<MatchResult> {
+        let idx = self.outcome_letters.iter().position(|l| l == letter)?;
+        Some(RESULTS[idx])
+    }
+
+    /// `(opponent, mine)` for every round of the guide. Blank lines are
+    /// skipped; any other line needs exactly two columns.
+    pub fn parse_rounds(
+        &self,
+        input: &str,
+        strategy: Strategy,
+    ) -> Result<Vec<(Shape, Shape)>, ParseError> {
+        let mut rounds = Vec::new();
+        for (idx, line) in input.lines().enumerate() {
+            let mut it = line.trim().split_ascii_whitespace();
+            match it.clone().count() {
+                0 => continue,
+                2 => {}
+                _ => {
+                    return Err(ParseError {
+                        line: idx + 1,
+                        text: line.trim().to_string(),
+                    })
+                }
+            }
+
+            let error = || ParseError {
+                line: idx + 1,
+                text: line.trim().to_string(),
+            };
+            let opponent = self.parse_opponent(it.next().unwrap()).ok_or_else(error)?;
+            let column = it.next().unwrap();
+            let mine = match strategy {
+                Strategy::Shape => self.parse_response(column),
+                Strategy::Outcome => self
+                    .parse_outcome(column)
+                    .and_then(|result| self.choose(opponent, result)),
+            }
+            .ok_or_else(error)?;
+            rounds.push((opponent, mine));
+        }
+        Ok(rounds)
+    }
+
+    pub fn total_score(&self, input: &str, strategy: Strategy) -> Result<u32, ParseError> {
+        let rounds = self.parse_rounds(input, strategy)?;
+        Ok(rounds
+            .into_iter()
+            .map(|(opponent, mine)| self.score(mine, opponent))
+            .sum())
+    }
+
+    /// Score details of every round of the guide.
+    pub fn breakdown(&self, input: &str, strategy: Strategy) -> Result<Vec<Round>, ParseError> {
+        let rounds = self.parse_rounds(input, strategy)?;
+        Ok(rounds
+            .into_iter()
+            .map(|(opponent, mine)| {
+                let result = self.outcome(mine, opponent);
+                Round {
+                    opponent,
+                    mine,
+                    result,
+                    shape_score: self.shape_score(mine),
+                    outcome_score: self.outcome_score(result),
+                }
+            })
+            .collect())
+    }
+
+    /// Every letter-to-shape permutation followed by every letter-to-outcome
+    /// permutation; the first of each is the game's own decoding.
+    pub fn decodings(&self) -> Vec<Decoding> {
+        let shapes = permutations(self.shape_count())
+            .into_iter()
+            .map(|meanings| Decoding {
+                strategy: Strategy::Shape,
+                meanings,
+            });
+        let outcomes = permutations(3).into_iter().map(|meanings| Decoding {
+            strategy: Strategy::Outcome,
+            meanings,
+        });
+        shapes.chain(outcomes).collect()
+    }
+
+    /// This game with its second-column letters reassigned per `decoding`.
+    pub fn decode(&self, decoding: &Decoding) -> Game {
+        let mut game = self.clone();
+        match decoding.strategy {
+            Strategy::Shape => {
+                for (letter, &shape) in decoding.meanings.iter().enumerate() {
+                    game.response_letters[shape] = self.response_letters[letter].clone();
+                }
+            }
+            Strategy::Outcome => {
+                for (letter, &result) in decoding.meanings.iter().enumerate() {
+                    game.outcome_letters[result] = self.outcome_letters[letter].clone();
+                }
+            }
+        }
+        game
+    }
+
+    /// e.g. `X=Rock Y=Paper Z=Scissors` or `X=Lose Y=Draw Z=Win`
+    pub fn describe(&self, decoding: &Decoding) -> String {
+        let pairs: Vec<String> = decoding
+            .meanings
+            .iter()
+            .enumerate()
+            .map(|(letter, &meaning)| match decoding.strategy {
+                Strategy::Shape => {
+                    format!("{}={}", self.response_letters[letter], self.names[meaning])
+                }
+                Strategy::Outcome => {
+                    format!("{}={:?}", self.outcome_letters[letter], RESULTS[meaning])
+                }
+            })
+            .collect();
+        pairs.join(" ")
+    }
+
+    /// The total of the guide under every decoding, in `decodings` order.
+    pub fn analyze(&self, input: &str) -> Result<Vec<DecodingReport>, ParseError> {
+        self.decodings()
+            .into_iter()
+            .map(|decoding| {
+                let total = self
+                    .decode(&decoding)
+                    .total_score(input, decoding.strategy)?;
+                Ok(DecodingReport { decoding, total })
+            })
+            .collect()
+    }
+
+    /// The highest-scoring decoding, the earliest one on ties.
+    pub fn best_decoding(&self, input: &str) -> Result<DecodingReport, ParseError> {
+        let reports = self.analyze(input)?;
+        let best = reports
+            .iter()
+            .rev()
+            .max_by_key(|report| report.total)
+            .unwrap();
+        Ok(best.clone())
+    }
+
+    /// The lowest-scoring decoding, the earliest one on ties.
+    pub fn worst_decoding(&self, input: &str) -> Result<DecodingReport, ParseError> {
+        let reports = self.analyze(input)?;
+        let worst = reports.iter().min_by_key(|report| report.total).unwrap();
+        Ok(worst.clone())
+    }
 }
 
-fn expected_rps(other: RPS, result: MatchResult) -> RPS {
-    match (other, result) {
-        (RPS::Rock, MatchResult::Win) => RPS::Paper,
-        (RPS::Rock, MatchResult::Draw) => RPS::Rock,
-        (RPS::Rock, MatchResult::Lose) => RPS::Scissors,
-        (RPS::Paper, MatchResult::Win) => RPS::Scissors,
-        (RPS::Paper, MatchResult::Draw) => RPS::Paper,
-        (RPS::Paper, MatchResult::Lose) => RPS::Rock,
-        (RPS::Scissors, MatchResult::Win) => RPS::Rock,
-        (RPS::Scissors, MatchResult::Draw) => RPS::Scissors,
-        (RPS::Scissors, MatchResult::Lose) => RPS::Paper,
+impl Game {
+    /// How often the opponent plays each shape in the guide's first column.
+    pub fn opponent_frequencies(&self, input: &str) -> Result<Vec<f64>, ParseError> {
+        let mut counts = vec![0_usize; self.shape_count()];
+        for (idx, line) in input.lines().enumerate() {
+            let mut it = line.trim().split_ascii_whitespace();
+            match it.clone().count() {
+                0 => continue,
+                2 => {}
+                _ => {
+                    return Err(ParseError {
+                        line: idx + 1,
+                        text: line.trim().to_string(),
+                    })
+                }
+            }
+
+            let shape = self.parse_opponent(it.next().unwrap()).ok_or(ParseError {
+                line: idx + 1,
+                text: line.trim().to_string(),
+            })?;
+            counts[shape] += 1;
+        }
+
+        let rounds = counts.iter().sum::<usize>().max(1) as f64;
+        Ok(counts
+            .into_iter()
+            .map(|count| count as f64 / rounds)
+            .collect())
+    }
+
+    /// Expected per-round score of playing shape `i` with probability `mix[i]`
+    /// against an opponent who plays shape `j` with probability `opponent[j]`.
+    pub fn expected_score(&self, mix: &[f64], opponent: &[f64]) -> f64 {
+        let mut expected = 0.0;
+        for (mine, &p) in mix.iter().enumerate() {
+            for (other, &q) in opponent.iter().enumerate() {
+                expected += p * q * self.score(mine, other) as f64;
+            }
+        }
+        expected
+    }
+
+    /// The pure strategy scoring best against `opponent`, the first on ties.
+    pub fn best_response(&self, opponent: &[f64]) -> (Shape, f64) {
+        let mut best = (0, f64::MIN);
+        for mine in 0..self.shape_count() {
+            let mut pure = vec![0.0; self.shape_count()];
+            pure[mine] = 1.0;
+            let expected = self.expected_score(&pure, opponent);
+            if expected > best.1 + EPSILON {
+                best = (mine, expected);
+            }
+        }
+        best
+    }
+
+    /// Found by support enumeration, largest supports first, so a fully mixed
+    /// equilibrium is preferred when there is one. Supports are generated one
+    /// at a time and the search stops at the first equilibrium.
+    ///
+    /// `None` for degenerate games whose equilibria only solve singular
+    /// systems, which support enumeration can't find.
+    pub fn nash_equilibrium(&self) -> Option<Equilibrium> {
+        let n = self.shape_count();
+        (1..=n)
+            .rev()
+            .flat_map(|size| Combinations::new(n, size))
+            .find_map(|support| self.equilibrium_on(&support))
+    }
+
+    // Makes every shape in `support` equally good against the mix, then checks
+    // that nothing outside it does better.
+    fn equilibrium_on(&self, support: &[Shape]) -> Option<Equilibrium> {
+        let k = support.len();
+        // unknowns: the k probabilities, then the value
+        let mut rows = Vec::with_capacity(k + 1);
+        for &mine in support {
+            let mut row: Vec<f64> = support
+                .iter()
+                .map(|&other| self.score(mine, other) as f64)
+                .collect();
+            row.extend([-1.0, 0.0]);
+            rows.push(row);
+        }
+        let mut total = vec![1.0; k];
+        total.extend([0.0, 1.0]);
+        rows.push(total);
+
+        let solution = solve_linear(rows)?;
+        if solution[..k].iter().any(|&p| p < -EPSILON) {
+            return None;
+        }
+
+        let mut mix = vec![0.0; self.shape_count()];
+        for (&shape, &p) in support.iter().zip(&solution) {
+            mix[shape] = p.max(0.0);
+        }
+        let value = solution[k];
+        let (_, best) = self.best_response(&mix);
+        (best <= value + EPSILON).then_some(Equilibrium { mix, value })
     }
 }
 
-pub fn total_score_part1(input: &str) -> u32 {
-    let mut total = 0;
-    for line in input.lines() {
-        let mut it = line.trim().split_ascii_whitespace();
-        if it.clone().count() != 2 {
-            continue;
+// the `size`-element subsets of 0..n in lexicographic order
+struct Combinations {
+    n: usize,
+    next: Option<Vec<usize>>,
+}
+
+impl Combinations {
+    fn new(n: usize, size: usize) -> Self {
+        Combinations {
+            n,
+            next: (size <= n).then(|| (0..size).collect()),
         }
+    }
+}
+
+impl Iterator for Combinations {
+    type Item = Vec<usize>;
 
-        let opponent: RPS = it.next().unwrap().into();
-        let mine: RPS = it.next().unwrap().into();
-        let score = score(mine, opponent);
-        total += score
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let current = self.next.take()?;
+        let size = current.len();
+        // bump the last index that still has room, then pack the rest after it
+        if let Some(i) = (0..size).rev().find(|&i| current[i] < self.n - size + i) {
+            let mut following = current.clone();
+            following[i] += 1;
+            for j in i + 1..size {
+                following[j] = following[j - 1] + 1;
+            }
+            self.next = Some(following);
+        }
+        Some(current)
     }
-    total
 }
 
-pub fn total_score_part2(input: &str) -> u32 {
-    let mut total = 0;
-    for line in input.lines() {
-        let mut it = line.trim().split_ascii_whitespace();
-        if it.clone().count() != 2 {
-            continue;
+// Gauss-Jordan elimination on an augmented matrix, None if it's singular.
+fn solve_linear(mut rows: Vec<Vec<f64>>) -> Option<Vec<f64>> {
+    let n = rows.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))?;
+        if rows[pivot][col].abs() < EPSILON {
+            return None;
         }
+        rows.swap(col, pivot);
 
-        let opponent: RPS = it.next().unwrap().into();
-        let guide: MatchResult = it.next().unwrap().into();
-        let mine = expected_rps(opponent, guide);
-        let score = score(mine, opponent);
-        total += score
+        let pivot_row = rows[col].clone();
+        for (idx, row) in rows.iter_mut().enumerate() {
+            if idx != col {
+                let factor = row[col] / pivot_row[col];
+                for (value, pivot_value) in row.iter_mut().zip(&pivot_row).skip(col) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
     }
-    total
+    Some((0..n).map(|i| rows[i][n] / rows[i][i]).collect())
+}
+
+// all orderings of 0..n, in lexicographic order
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    fn extend(current: &mut Vec<usize>, used: &mut [bool], out: &mut Vec<Vec<usize>>) {
+        if current.len() == used.len() {
+            out.push(current.clone());
+            return;
+        }
+        for i in 0..used.len() {
+            if !used[i] {
+                used[i] = true;
+                current.push(i);
+                extend(current, used, out);
+                current.pop();
+                used[i] = false;
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    extend(&mut Vec::new(), &mut vec![false; n], &mut out);
+    out
+}
+
+pub fn total_score_part1(input: &str) -> u32 {
+    try_total_score_part1(input).unwrap()
+}
+
+pub fn total_score_part2(input: &str) -> u32 {
+    try_total_score_part2(input).unwrap()
+}
+
+pub fn try_total_score_part1(input: &str) -> Result<u32, ParseError> {
+    RPS::game().total_score(input, Strategy::Shape)
+}
+
+pub fn try_total_score_part2(input: &str) -> Result<u32, ParseError> {
+    RPS::game().total_score(input, Strategy::Outcome)
 }
 
 #[cfg(test)]
@@ -146,4 +707,264 @@ mod tests {
         let score = total_score_part2(input);
         assert_eq!(score, 13433);
     }
+
+    #[test]
+    fn test_rps_scores() {
+        assert_eq!(score(RPS::Paper, RPS::Rock), 8);
+        assert_eq!(score(RPS::Rock, RPS::Paper), 1);
+        assert_eq!(score(RPS::Scissors, RPS::Scissors), 6);
+        assert_eq!(RPS::Rock.beats_score(RPS::Scissors), 6);
+    }
+
+    #[test]
+    fn test_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        for mine in 0..game.shape_count() {
+            let wins = (0..game.shape_count())
+                .filter(|&other| game.beats(mine, other))
+                .count();
+            assert_eq!(wins, 2, "{}", game.name(mine));
+        }
+
+        let shape = |name| game.shape(name).unwrap();
+        assert!(game.beats(shape("Spock"), shape("Scissors")));
+        assert!(game.beats(shape("Lizard"), shape("Paper")));
+        assert!(game.beats(shape("Rock"), shape("Lizard")));
+        assert!(game.beats(shape("Paper"), shape("Spock")));
+
+        // Spock (4) and Lizard (5) both beat Paper
+        assert_eq!(
+            game.choose(shape("Paper"), MatchResult::Win),
+            Some(shape("Lizard"))
+        );
+        assert_eq!(game.parse_response("V"), Some(shape("Rock")));
+
+        let score = game.total_score("A Y\nD V\nE Z", Strategy::Shape);
+        assert_eq!(score, Ok(10 + 1 + 8));
+    }
+
+    #[test]
+    fn test_custom_game() {
+        // Well beats Rock and Scissors, Paper covers the Well
+        let beats = vec![
+            vec![false, false, true, false],
+            vec![true, false, false, true],
+            vec![false, true, false, false],
+            vec![true, false, true, false],
+        ];
+        let game = Game::new(
+            &["Rock", "Paper", "Scissors", "Well"],
+            Dominance::Table(beats),
+        )
+        .unwrap()
+        .with_shape_scores(&[1, 2, 3, 2])
+        .with_outcome_scores(0, 1, 2)
+        .with_opponent_letters(&["r", "p", "s", "w"])
+        .with_response_letters(&["R", "P", "S", "W"])
+        .with_outcome_letters("L", "D", "W");
+
+        assert_eq!(game.outcome(3, 0), MatchResult::Win);
+        assert_eq!(game.outcome(1, 3), MatchResult::Win);
+        assert_eq!(game.choose(0, MatchResult::Win), Some(1));
+        assert_eq!(game.choose(1, MatchResult::Win), Some(2));
+        assert_eq!(game.choose(0, MatchResult::Lose), Some(2));
+
+        let input = "r W\ns R\nw P";
+        assert_eq!(game.total_score(input, Strategy::Shape), Ok(4 + 3 + 4));
+        let input = "r W\nw L";
+        assert_eq!(game.total_score(input, Strategy::Outcome), Ok(4 + 3));
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let game = Game::rock_paper_scissors();
+        let rounds = game.breakdown("A Y\nB X\nC Z", Strategy::Outcome).unwrap();
+        assert_eq!(
+            rounds[0],
+            Round {
+                opponent: 0,
+                mine: 0,
+                result: MatchResult::Draw,
+                shape_score: 1,
+                outcome_score: 3,
+            }
+        );
+        assert_eq!(rounds[1].mine, 0);
+        assert_eq!(rounds[1].result, MatchResult::Lose);
+        assert_eq!(
+            rounds.iter().map(Round::score).collect::<Vec<_>>(),
+            [4, 1, 7]
+        );
+    }
+
+    #[test]
+    fn test_analyze_sample() {
+        let game = Game::rock_paper_scissors();
+        let reports = game.analyze("A Y\nB X\nC Z").unwrap();
+        assert_eq!(reports.len(), 12);
+        assert_eq!(reports[0].total, 15);
+        assert_eq!(reports[6].total, 12);
+        assert_eq!(
+            game.describe(&reports[0].decoding),
+            "X=Rock Y=Paper Z=Scissors"
+        );
+        assert_eq!(game.describe(&reports[6].decoding), "X=Lose Y=Draw Z=Win");
+
+        // reading X/Y/Z backwards wins every round, swapping Y and Z loses them all
+        let best = game.best_decoding("A Y\nB X\nC Z").unwrap();
+        assert_eq!(game.describe(&best.decoding), "X=Scissors Y=Paper Z=Rock");
+        assert_eq!(best.total, 8 + 9 + 7);
+        let worst = game.worst_decoding("A Y\nB X\nC Z").unwrap();
+        assert_eq!(game.describe(&worst.decoding), "X=Rock Y=Scissors Z=Paper");
+        assert_eq!(worst.total, 1 + 2 + 3);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let input = include_str!("../input/day_02.txt");
+        let game = Game::rock_paper_scissors();
+        let reports = game.analyze(input).unwrap();
+        assert_eq!(reports[0].total, 13484);
+        assert_eq!(reports[6].total, 13433);
+
+        let best = game.best_decoding(input).unwrap();
+        assert!(reports.iter().all(|report| report.total <= best.total));
+        assert_eq!(game.describe(&best.decoding), "X=Lose Y=Win Z=Draw");
+        assert_eq!(best.total, 14841);
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_expected_score() {
+        let game = Game::rock_paper_scissors();
+        let uniform = [1.0 / 3.0; 3];
+        // 2 for the average shape, 3 for the average outcome
+        assert_close(game.expected_score(&uniform, &uniform), 5.0);
+        assert_close(game.expected_score(&[0.0, 1.0, 0.0], &[1.0, 0.0, 0.0]), 8.0);
+        assert_eq!(game.best_response(&[1.0, 0.0, 0.0]), (1, 8.0));
+    }
+
+    #[test]
+    fn test_best_response() {
+        let input = include_str!("../input/day_02.txt");
+        let game = Game::rock_paper_scissors();
+        let opponent = game.opponent_frequencies(input).unwrap();
+        assert_close(opponent[0], 584.0 / 2500.0);
+        assert_close(opponent[2], 1220.0 / 2500.0);
+
+        let (shape, expected) = game.best_response(&opponent);
+        assert_eq!(game.name(shape), "Scissors");
+        assert_close(expected, 15336.0 / 2500.0);
+    }
+
+    #[test]
+    fn test_nash_equilibrium() {
+        // scissors pays the most but loses to rock, which in turn scores little
+        let game = Game::rock_paper_scissors();
+        let equilibrium = game.nash_equilibrium().unwrap();
+        for (p, expected) in equilibrium
+            .mix
+            .iter()
+            .zip([4.0 / 9.0, 1.0 / 9.0, 4.0 / 9.0])
+        {
+            assert_close(*p, expected);
+        }
+        assert_close(equilibrium.value, 5.0);
+
+        // with flat shape scores the classic uniform mix comes back
+        let game = Game::rock_paper_scissors_lizard_spock().with_shape_scores(&[0; 5]);
+        let equilibrium = game.nash_equilibrium().unwrap();
+        assert!(equilibrium.mix.iter().all(|&p| (p - 0.2).abs() < 1e-9));
+        assert_close(equilibrium.value, 3.0);
+    }
+
+    #[test]
+    fn test_degenerate_equilibrium() {
+        // a second rock with the same payoffs makes the fully mixed system
+        // singular; a smaller support still has the equilibrium
+        let beats = vec![
+            vec![false, false, false, true],
+            vec![false, false, false, true],
+            vec![true, true, false, false],
+            vec![false, false, true, false],
+        ];
+        let game = Game::new(
+            &["Rock", "Rock", "Paper", "Scissors"],
+            Dominance::Table(beats),
+        )
+        .unwrap()
+        .with_shape_scores(&[0; 4]);
+        let equilibrium = game.nash_equilibrium().unwrap();
+        assert_close(equilibrium.mix[0] + equilibrium.mix[1], 1.0 / 3.0);
+        assert_close(equilibrium.mix[2], 1.0 / 3.0);
+        assert_close(equilibrium.value, 3.0);
+        let (_, best) = game.best_response(&equilibrium.mix);
+        assert_close(best, equilibrium.value);
+
+        // only draws: every support but a single shape is singular
+        let game = Game::new(&["A", "B", "C"], Dominance::Table(vec![vec![false; 3]; 3]))
+            .unwrap()
+            .with_shape_scores(&[1; 3]);
+        let equilibrium = game.nash_equilibrium().unwrap();
+        assert_eq!(equilibrium.mix, [1.0, 0.0, 0.0]);
+        assert_close(equilibrium.value, 4.0);
+    }
+
+    #[test]
+    fn test_combinations() {
+        let all: Vec<Vec<usize>> = Combinations::new(4, 2).collect();
+        assert_eq!(all, [[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]]);
+        assert_eq!(Combinations::new(3, 3).count(), 1);
+        assert_eq!(Combinations::new(2, 3).count(), 0);
+        // 26 shapes never list all 2^26 supports up front
+        assert_eq!(Combinations::new(26, 13).take(5).count(), 5);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let game = Game::rock_paper_scissors();
+        assert_eq!(
+            game.total_score("A Y\nD X", Strategy::Shape),
+            Err(ParseError {
+                line: 2,
+                text: "D X".to_string()
+            })
+        );
+        assert!(game.total_score("A Q", Strategy::Outcome).is_err());
+        assert_eq!(
+            game.total_score("A Y\n\nB\nC Z", Strategy::Shape),
+            Err(ParseError {
+                line: 3,
+                text: "B".to_string()
+            })
+        );
+        assert!(game.opponent_frequencies("A Y X").is_err());
+    }
+
+    #[test]
+    fn test_game_errors() {
+        assert_eq!(
+            Game::new(&["Rock", "Paper"], Dominance::Cyclic),
+            Err(GameError::EvenCycle(2))
+        );
+        assert_eq!(
+            Game::new(&[], Dominance::Cyclic),
+            Err(GameError::ShapeCount(0))
+        );
+        assert_eq!(
+            Game::new(&["Rock", "Paper"], Dominance::Table(vec![vec![false]])),
+            Err(GameError::TableSize(2))
+        );
+        let beats = vec![vec![false, true], vec![true, false]];
+        assert_eq!(
+            Game::new(&["Rock", "Paper"], Dominance::Table(beats)),
+            Err(GameError::MutualWin(
+                "Rock".to_string(),
+                "Paper".to_string()
+            ))
+        );
+    }
 }
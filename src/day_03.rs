@@ -1,15 +1,136 @@
-use std::collections::{hash_map::RandomState, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupError {
+    InvalidItem { line: usize, item: char }, // 1-based line
+    NoCommonItem { line: usize },            // first line of the group
+    Incomplete { lines: usize, group_size: usize },
+    EmptyGroup,
+}
+
+impl fmt::Display for GroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupError::InvalidItem { line, item } => {
+                write!(f, "line {line}: invalid item {item:?}")
+            }
+            GroupError::NoCommonItem { line } => {
+                write!(f, "line {line}: group has no common item")
+            }
+            GroupError::Incomplete { lines, group_size } => {
+                write!(
+                    f,
+                    "{lines} rucksacks can't be split into groups of {group_size}"
+                )
+            }
+            GroupError::EmptyGroup => write!(f, "group size must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for GroupError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RucksackError {
+    InvalidItem { line: usize, item: char }, // 1-based line
+    NoSharedItem { line: usize },
+}
+
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RucksackError::InvalidItem { line, item } => {
+                write!(f, "line {line}: invalid item {item:?}")
+            }
+            RucksackError::NoSharedItem { line } => {
+                write!(f, "line {line}: compartments share no item")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RucksackError {}
+
+/// Set of item types, bit `p` set for the item with priority `p` (1..=52).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const ALL: ItemSet = ItemSet(((1 << 52) - 1) << 1);
+
+    /// `None` if `items` has anything but a-z and A-Z.
+    pub fn from_items(items: &[u8]) -> Option<Self> {
+        let mut set = ItemSet::default();
+        for &item in items {
+            set.insert(item)?;
+        }
+        Some(set)
+    }
+
+    /// Adds `item`, `None` if it isn't a letter.
+    pub fn insert(&mut self, item: u8) -> Option<()> {
+        self.0 |= 1 << priority(item)?;
+        Some(())
+    }
+
+    pub fn contains(&self, item: u8) -> bool {
+        priority(item).is_some_and(|p| self.0 & (1 << p) != 0)
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Items in priority order, a-z then A-Z.
+    pub fn items(&self) -> impl Iterator<Item = u8> + '_ {
+        (1..=52)
+            .filter(|p| self.0 & (1 << p) != 0)
+            .map(priority_to_item)
+    }
+
+    pub fn priority_sum(&self) -> u32 {
+        (1..=52).filter(|p| self.0 & (1 << p) != 0).sum()
+    }
+}
 
 pub fn solve_part1(input: &str) -> u32 {
+    try_solve_part1(input).unwrap()
+}
+
+/// Sum of the priorities of the item in both compartments of each rucksack.
+/// Blank lines are ignored.
+pub fn try_solve_part1(input: &str) -> Result<u32, RucksackError> {
     let mut sum = 0;
-    for line in input.lines() {
+    for (idx, line) in input.lines().enumerate() {
         let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(item) = line.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(RucksackError::InvalidItem {
+                line: idx + 1,
+                item,
+            });
+        }
+
         let (left, right) = divide_line(line);
-        let in_both = find_both_compartments(left, right).unwrap();
-        let priority = aplha_to_priority(in_both);
-        sum += priority;
+        let in_both = find_both_compartments(left, right)
+            .ok_or(RucksackError::NoSharedItem { line: idx + 1 })?;
+        sum += aplha_to_priority(in_both);
     }
-    sum
+    Ok(sum)
 }
 
 fn divide_line(line: &str) -> (&str, &str) {
@@ -17,67 +138,257 @@ fn divide_line(line: &str) -> (&str, &str) {
     line.split_at(len / 2)
 }
 
-fn aplha_to_priority(c: u8) -> u32 {
+fn priority(c: u8) -> Option<u32> {
     match c {
-        b'a'..=b'z' => (c - b'a') as u32 + 1,
-        b'A'..=b'Z' => (c - b'A') as u32 + 27,
-        _ => panic!("Invalid input"),
+        b'a'..=b'z' => Some((c - b'a') as u32 + 1),
+        b'A'..=b'Z' => Some((c - b'A') as u32 + 27),
+        _ => None,
     }
 }
 
+fn priority_to_item(p: u32) -> u8 {
+    match p {
+        1..=26 => b'a' + (p - 1) as u8,
+        _ => b'A' + (p - 27) as u8,
+    }
+}
+
+fn aplha_to_priority(c: u8) -> u32 {
+    priority(c).expect("Invalid input")
+}
+
 fn find_both_compartments(left: &str, right: &str) -> Option<u8> {
-    let (mut i, mut j) = (0, 0);
-    let (mut left, mut right): (Vec<_>, Vec<_>) = (
-        left.as_bytes().iter().collect(),
-        right.as_bytes().iter().collect(),
-    );
-    left.sort();
-    right.sort();
+    let left = ItemSet::from_items(left.as_bytes())?;
+    let right = ItemSet::from_items(right.as_bytes())?;
+    left.intersection(right).items().next()
+}
 
-    while i < left.len() && j < right.len() {
-        if left[i] == right[j] {
-            return Some(*left[i]);
+pub fn solve_part2(input: &str) -> u32 {
+    try_solve_part2(input).unwrap()
+}
+
+pub fn try_solve_part2(input: &str) -> Result<u32, GroupError> {
+    solve_groups(input, 3)
+}
+
+/// Sum of the priorities of the items common to each group of `group_size`
+/// consecutive rucksacks. Blank lines are ignored.
+pub fn solve_groups(input: &str, group_size: usize) -> Result<u32, GroupError> {
+    if group_size == 0 {
+        return Err(GroupError::EmptyGroup);
+    }
+
+    let mut rucksacks = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
         }
-        if left[i] < right[j] {
-            i += 1;
-        } else {
-            j += 1;
+        let items = match line.bytes().find(|&item| priority(item).is_none()) {
+            Some(item) => {
+                return Err(GroupError::InvalidItem {
+                    line: idx + 1,
+                    item: item as char,
+                })
+            }
+            None => ItemSet::from_items(line.as_bytes()).unwrap(),
+        };
+        rucksacks.push((idx + 1, items));
+    }
+
+    if rucksacks.len() % group_size != 0 {
+        return Err(GroupError::Incomplete {
+            lines: rucksacks.len(),
+            group_size,
+        });
+    }
+
+    let mut sum = 0;
+    for group in rucksacks.chunks(group_size) {
+        let common = group.iter().fold(ItemSet::ALL, |common, (_, items)| {
+            common.intersection(*items)
+        });
+        if common.is_empty() {
+            return Err(GroupError::NoCommonItem { line: group[0].0 });
         }
+        sum += common.priority_sum();
     }
+    Ok(sum)
+}
 
-    None
+pub fn find_badge(line_a: &str, line_b: &str, line_c: &str) -> Option<u8> {
+    let [a, b, c] =
+        [line_a, line_b, line_c].map(|line| ItemSet::from_items(line.trim().as_bytes()));
+    a?.intersection(b?).intersection(c?).items().next()
 }
 
-pub fn solve_part2(input: &str) -> u32 {
-    let mut sum = 0;
-    let mut lines = input.lines();
-    loop {
-        let Some(line_a) = lines.next() else {
-            break;
-        };
-        let line_b = lines.next().unwrap();
-        let line_c = lines.next().unwrap();
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    OddLength,
+    InvalidItems(Vec<char>),
+    NoOverlap,
+}
 
-        let badge = find_badge(line_a, line_b, line_c).unwrap();
-        sum += aplha_to_priority(badge);
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::OddLength => write!(f, "odd length"),
+            Issue::InvalidItems(items) => {
+                let items: String = items.iter().collect();
+                write!(f, "invalid items {items:?}")
+            }
+            Issue::NoOverlap => write!(f, "no overlap"),
+        }
     }
+}
 
-    sum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RucksackAudit {
+    pub line: usize, // 1-based
+    pub shared: ItemSet,
+    pub issues: Vec<Issue>,
 }
 
-fn find_badge(line_a: &str, line_b: &str, line_c: &str) -> Option<u8> {
-    let line_a: HashSet<u8, RandomState> = HashSet::from_iter(line_a.trim().bytes());
-    let line_b: HashSet<u8, RandomState> = HashSet::from_iter(line_b.trim().bytes());
-    let line_c: HashSet<u8, RandomState> = HashSet::from_iter(line_c.trim().bytes());
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ItemTotal {
+    pub item: u8,
+    pub count: usize,     // occurrences over all rucksacks
+    pub rucksacks: usize, // rucksacks holding at least one
+    pub shared: usize,    // rucksacks holding it in both compartments
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditReport {
+    pub rucksacks: Vec<RucksackAudit>,
+    pub item_totals: Vec<ItemTotal>, // priority order, unseen items left out
+}
+
+impl AuditReport {
+    pub fn flagged(&self) -> impl Iterator<Item = &RucksackAudit> {
+        self.rucksacks.iter().filter(|r| !r.issues.is_empty())
+    }
 
-    let line_ab = line_a
-        .intersection(&line_b)
-        .into_iter()
-        .copied()
-        .collect::<HashSet<u8, RandomState>>();
+    /// Sum of priorities of every item shared between compartments.
+    pub fn shared_priority_sum(&self) -> u32 {
+        self.rucksacks.iter().map(|r| r.shared.priority_sum()).sum()
+    }
+}
+
+/// Checks every rucksack instead of stopping at the first shared item.
+/// Invalid items are reported and otherwise ignored; blank lines are skipped.
+pub fn audit(input: &str) -> AuditReport {
+    let mut counts = [(0, 0, 0); 53];
+    let mut rucksacks = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut issues = Vec::new();
+        if line.len() % 2 != 0 {
+            issues.push(Issue::OddLength);
+        }
+        let invalid: Vec<char> = line
+            .chars()
+            .filter(|c| !c.is_ascii() || priority(*c as u8).is_none())
+            .collect();
+        if !invalid.is_empty() {
+            issues.push(Issue::InvalidItems(invalid));
+        }
+
+        let (left, right) = line.as_bytes().split_at(line.len() / 2);
+        let [left, right] = [left, right].map(|items| {
+            let mut set = ItemSet::default();
+            for &item in items {
+                set.insert(item);
+            }
+            set
+        });
+        let shared = left.intersection(right);
+        if shared.is_empty() {
+            issues.push(Issue::NoOverlap);
+        }
+
+        for p in line.bytes().filter_map(priority) {
+            counts[p as usize].0 += 1;
+        }
+        for item in left.union(right).items() {
+            counts[aplha_to_priority(item) as usize].1 += 1;
+        }
+        for item in shared.items() {
+            counts[aplha_to_priority(item) as usize].2 += 1;
+        }
+
+        rucksacks.push(RucksackAudit {
+            line: idx + 1,
+            shared,
+            issues,
+        });
+    }
+
+    let item_totals = (1..=52)
+        .filter(|&p| counts[p].0 > 0)
+        .map(|p| ItemTotal {
+            item: priority_to_item(p as u32),
+            count: counts[p].0,
+            rucksacks: counts[p].1,
+            shared: counts[p].2,
+        })
+        .collect();
+
+    AuditReport {
+        rucksacks,
+        item_totals,
+    }
+}
+
+/// One row per rucksack with its shared items and issues.
+pub fn render_rucksacks(report: &AuditReport) -> String {
+    let shared: Vec<String> = report
+        .rucksacks
+        .iter()
+        .map(|r| r.shared.items().map(char::from).collect())
+        .collect();
+    let shared_width = shared.iter().map(String::len).max().unwrap_or(0).max(6);
+
+    let mut result = format!(
+        "{:>4}  {:<shared_width$}  {:>8}  Issues\n",
+        "Line", "Shared", "Priority"
+    );
+    for (rucksack, shared) in report.rucksacks.iter().zip(shared) {
+        let issues: Vec<String> = rucksack.issues.iter().map(Issue::to_string).collect();
+        let row = format!(
+            "{:>4}  {:<shared_width$}  {:>8}  {}",
+            rucksack.line,
+            shared,
+            rucksack.shared.priority_sum(),
+            issues.join(", ")
+        );
+        result += row.trim_end();
+        result += "\n";
+    }
+    result
+}
 
-    let mut line_abc = line_ab.intersection(&line_c).into_iter().copied();
-    line_abc.next()
+/// One row per item type seen in the input.
+pub fn render_item_totals(report: &AuditReport) -> String {
+    let mut result = format!(
+        "{:>4}  {:>8}  {:>5}  {:>9}  {:>6}\n",
+        "Item", "Priority", "Count", "Rucksacks", "Shared"
+    );
+    for total in &report.item_totals {
+        result += &format!(
+            "{:>4}  {:>8}  {:>5}  {:>9}  {:>6}\n",
+            total.item as char,
+            aplha_to_priority(total.item),
+            total.count,
+            total.rucksacks,
+            total.shared
+        );
+    }
+    result
 }
 
 #[cfg(test)]
@@ -115,6 +426,22 @@ mod tests {
 
         let answer = solve_part1(sample);
         assert_eq!(answer, 157);
+
+        assert_eq!(try_solve_part1("abcaxd\n\nAbAc"), Ok(1 + 27));
+        assert_eq!(
+            try_solve_part1("abcabd\nab-ab-"),
+            Err(RucksackError::InvalidItem { line: 2, item: '-' })
+        );
+        assert_eq!(
+            try_solve_part1("aébaéb"),
+            Err(RucksackError::InvalidItem {
+                line: 1, item: 'é'
+            })
+        );
+        assert_eq!(
+            try_solve_part1("abcdef"),
+            Err(RucksackError::NoSharedItem { line: 1 })
+        );
     }
 
     #[test]
@@ -137,6 +464,116 @@ mod tests {
         assert_eq!(Some(b'Z'), find_badge(line_a, line_b, line_c));
     }
 
+    #[test]
+    fn test_item_set() {
+        let set = ItemSet::from_items(b"vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+        assert!(set.contains(b'v'));
+        assert!(!set.contains(b'a'));
+        assert_eq!(set.len(), 14);
+
+        let a = ItemSet::from_items(b"abcZ").unwrap();
+        let b = ItemSet::from_items(b"cdZ").unwrap();
+        assert_eq!(a.intersection(b).items().collect::<Vec<_>>(), b"cZ");
+        assert_eq!(a.union(b).priority_sum(), 1 + 2 + 3 + 4 + 52);
+        assert_eq!(ItemSet::ALL.len(), 52);
+        assert!(ItemSet::from_items(b"ab1").is_none());
+    }
+
+    #[test]
+    fn test_solve_groups() {
+        let sample = r#"vJrwpWtwJgWrhcsFMMfFFhFp
+        jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+        PmmdzqPrVvPwwTWBwg
+        wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+        ttgJtRGJQctTZtZT
+        CrZsJsPPZsGzwwsLwLmpwMDw"#;
+
+        assert_eq!(solve_groups(sample, 3), Ok(18 + 52));
+        assert_eq!(solve_groups("ab\nA\n\n", 1), Ok(1 + 2 + 27));
+        assert_eq!(
+            solve_groups(sample, 4),
+            Err(GroupError::Incomplete {
+                lines: 6,
+                group_size: 4
+            })
+        );
+        assert_eq!(
+            solve_groups("abc\nxyz", 2),
+            Err(GroupError::NoCommonItem { line: 1 })
+        );
+        assert_eq!(
+            solve_groups("abc\na-c", 2),
+            Err(GroupError::InvalidItem { line: 2, item: '-' })
+        );
+        assert_eq!(solve_groups(sample, 0), Err(GroupError::EmptyGroup));
+    }
+
+    #[test]
+    fn test_audit() {
+        let input = "vJrwpWtwJgWrhcsFMMfFFhFp\nabcab\n\nab-xy\nabAa";
+        let report = audit(input);
+        assert_eq!(report.rucksacks.len(), 4);
+
+        assert_eq!(report.rucksacks[0].shared.items().collect::<Vec<_>>(), b"p");
+        assert!(report.rucksacks[0].issues.is_empty());
+
+        // "ab" | "cab": both a and b are shared
+        assert_eq!(
+            report.rucksacks[1].shared.items().collect::<Vec<_>>(),
+            b"ab"
+        );
+        assert_eq!(report.rucksacks[1].issues, [Issue::OddLength]);
+
+        assert_eq!(report.rucksacks[2].line, 4);
+        assert_eq!(
+            report.rucksacks[2].issues,
+            [
+                Issue::OddLength,
+                Issue::InvalidItems(vec!['-']),
+                Issue::NoOverlap
+            ]
+        );
+        assert_eq!(report.flagged().count(), 2);
+
+        let a = report.item_totals.iter().find(|t| t.item == b'a').unwrap();
+        assert_eq!((a.count, a.rucksacks, a.shared), (5, 3, 2));
+        assert_eq!(report.item_totals.last().unwrap().item, b'W');
+        assert_eq!(report.shared_priority_sum(), 16 + 1 + 2 + 1);
+    }
+
+    #[test]
+    fn test_audit_input() {
+        let input = include_str!("../input/day_03.txt");
+        let report = audit(input);
+        assert_eq!(report.flagged().count(), 0);
+        assert!(report.rucksacks.iter().all(|r| r.shared.len() == 1));
+        assert_eq!(report.shared_priority_sum(), 7817);
+    }
+
+    #[test]
+    fn test_render() {
+        let report = audit("abcab\nab-xy");
+        assert_eq!(
+            render_rucksacks(&report),
+            "\
+Line  Shared  Priority  Issues
+   1  ab             3  odd length
+   2                 0  odd length, invalid items \"-\", no overlap
+"
+        );
+        assert_eq!(
+            render_item_totals(&report),
+            "\
+Item  Priority  Count  Rucksacks  Shared
+   a         1      3          2       1
+   b         2      3          2       1
+   c         3      1          1       0
+   x        24      1          1       0
+   y        25      1          1       0
+"
+        );
+    }
+
     #[test]
     fn test_part2() {
         let input = include_str!("../input/day_03.txt");
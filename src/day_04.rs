@@ -1,49 +1,230 @@
+use std::fmt;
+
+use crate::interval::{Interval, IntervalSet};
+
+type Assignment = Interval<u32>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize, // 1-based
+    pub text: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: invalid assignment `{}`", self.line, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 pub fn solve_part1(input: &str) -> u32 {
+    try_solve_part1(input).unwrap()
+}
+
+pub fn solve_part2(input: &str) -> u32 {
+    try_solve_part2(input).unwrap()
+}
+
+pub fn try_solve_part1(input: &str) -> Result<u32, ParseError> {
+    count_pairs(input, |left, right| {
+        left.contains_interval(right) || right.contains_interval(left)
+    })
+}
+
+pub fn try_solve_part2(input: &str) -> Result<u32, ParseError> {
+    count_pairs(input, |left, right| left.overlaps(right))
+}
+
+fn count_pairs(
+    input: &str,
+    matches: impl Fn(&Assignment, &Assignment) -> bool,
+) -> Result<u32, ParseError> {
     let mut sum = 0;
-    for line in input.lines() {
-        let (left, right) = parse_line(line);
-        if is_fully_contains(left, right) || is_fully_contains(right, left) {
+    for (idx, line) in input.lines().enumerate() {
+        let (left, right) = parse_line(line).ok_or_else(|| ParseError {
+            line: idx + 1,
+            text: line.trim().to_string(),
+        })?;
+        if matches(&left, &right) {
             sum += 1;
         }
     }
-    sum
+    Ok(sum)
 }
 
-fn parse_line(line: &str) -> ((u32, u32), (u32, u32)) {
-    let mut iter = line.trim().split(',');
-    let range = iter.next().unwrap();
-    let range_a = parse_range(range);
+fn parse_line(line: &str) -> Option<(Assignment, Assignment)> {
+    let (range_a, range_b) = line.trim().split_once(',')?;
+    Some((parse_range(range_a)?, parse_range(range_b)?))
+}
 
-    let range = iter.next().unwrap();
-    let range_b = parse_range(range);
+fn parse_range(range: &str) -> Option<Assignment> {
+    let (start, end) = range.trim().split_once('-')?;
+    Interval::try_new(start.parse().ok()?, end.parse().ok()?)
+}
 
-    (range_a, range_b)
+/// The assignments on one line of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub line: usize, // 1-based
+    pub assignments: Vec<Assignment>,
 }
 
-fn parse_range(range: &str) -> (u32, u32) {
-    let mut iter = range.split('-');
-    let start = iter.next().unwrap().parse().unwrap();
-    let end = iter.next().unwrap().parse().unwrap();
-    (start, end)
+/// Every line, with any number of comma-separated assignments. Blank lines are
+/// skipped.
+pub fn parse_camp(input: &str) -> Result<Vec<Group>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            let assignments = line.trim().split(',').map(parse_range).collect();
+            match assignments {
+                Some(assignments) => Ok(Group {
+                    line: idx + 1,
+                    assignments,
+                }),
+                None => Err(ParseError {
+                    line: idx + 1,
+                    text: line.trim().to_string(),
+                }),
+            }
+        })
+        .collect()
 }
 
-fn is_fully_contains(range_a: (u32, u32), range_b: (u32, u32)) -> bool {
-    range_a.0 <= range_b.0 && range_a.1 >= range_b.1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CampCoverage {
+    pub bounds: Option<Assignment>, // lowest to highest assigned section
+    pub covered: IntervalSet<u32>,
+    pub uncovered: IntervalSet<u32>, // gaps within `bounds`
+    pub max_depth: usize,
+    pub max_depth_at: IntervalSet<u32>,
+    pub redundant_elves: Vec<(usize, usize)>, // (line, elf) covered by the rest of its group
+    pub redundant_groups: Vec<usize>,         // lines covered by all the other groups
 }
 
-pub fn solve_part2(input: &str) -> u32 {
-    let mut sum = 0;
-    for line in input.lines() {
-        let (left, right) = parse_line(line);
-        if is_overlap(left, right) {
-            sum += 1;
+pub fn camp_coverage(groups: &[Group]) -> CampCoverage {
+    let all = || groups.iter().flat_map(|group| &group.assignments);
+
+    let covered: IntervalSet<u32> = all().copied().collect();
+    let bounds = match (covered.intervals().first(), covered.intervals().last()) {
+        (Some(first), Some(last)) => Some(Interval::new(first.start(), last.end())),
+        _ => None,
+    };
+    let uncovered = match bounds {
+        Some(bounds) => covered.gaps(bounds),
+        None => IntervalSet::new(),
+    };
+    let depths = depth_profile(all().copied());
+    let max_depth = depths.iter().map(|&(_, depth)| depth).max().unwrap_or(0);
+    let max_depth_at = depths
+        .iter()
+        .filter(|&&(_, depth)| depth == max_depth)
+        .map(|&(sections, _)| sections)
+        .collect();
+
+    let mut redundant_elves = Vec::new();
+    for group in groups {
+        for (elf, assignment) in group.assignments.iter().enumerate() {
+            let others: IntervalSet<u32> = group
+                .assignments
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != elf)
+                .map(|(_, assignment)| *assignment)
+                .collect();
+            if others.covers(assignment) {
+                redundant_elves.push((group.line, elf));
+            }
+        }
+    }
+
+    // a group is redundant when each of its sections is also in another group
+    let unions: Vec<IntervalSet<u32>> = groups
+        .iter()
+        .map(|group| group.assignments.iter().copied().collect())
+        .collect();
+    let shared: IntervalSet<u32> = depth_profile(
+        unions
+            .iter()
+            .flat_map(|union| union.intervals().iter().copied()),
+    )
+    .into_iter()
+    .filter(|&(_, depth)| depth >= 2)
+    .map(|(sections, _)| sections)
+    .collect();
+    let redundant_groups = groups
+        .iter()
+        .zip(&unions)
+        .filter(|(_, union)| union.intervals().iter().all(|i| shared.covers(i)))
+        .map(|(group, _)| group.line)
+        .collect();
+
+    CampCoverage {
+        bounds,
+        covered,
+        uncovered,
+        max_depth,
+        max_depth_at,
+        redundant_elves,
+        redundant_groups,
+    }
+}
+
+// Sweeps over start/end events and returns each stretch of sections with how
+// many assignments cover it, leaving out uncovered stretches.
+fn depth_profile(assignments: impl Iterator<Item = Assignment>) -> Vec<(Assignment, usize)> {
+    // ends are placed just past the last section, so at the same position they
+    // sort before starts
+    let mut events: Vec<(u64, i32)> = assignments
+        .flat_map(|a| [(a.start() as u64, 1), (a.end() as u64 + 1, -1)])
+        .collect();
+    events.sort();
+
+    let mut profile = Vec::new();
+    let mut depth = 0;
+    for (idx, &(section, change)) in events.iter().enumerate() {
+        depth += change;
+        match events.get(idx + 1) {
+            Some(&(next, _)) if next > section && depth > 0 => {
+                let sections = Interval::new(section as u32, (next - 1) as u32);
+                profile.push((sections, depth as usize));
+            }
+            _ => {}
         }
     }
-    sum
+    profile
 }
 
-fn is_overlap(range_a: (u32, u32), range_b: (u32, u32)) -> bool {
-    u32::min(range_a.1, range_b.1) >= u32::max(range_a.0, range_b.0)
+/// Summary of `camp_coverage` for the runner.
+pub fn render_coverage(coverage: &CampCoverage) -> String {
+    let list = |set: &IntervalSet<u32>| {
+        let ranges: Vec<String> = set
+            .intervals()
+            .iter()
+            .map(|i| format!("{}-{}", i.start(), i.end()))
+            .collect();
+        if ranges.is_empty() {
+            "none".to_string()
+        } else {
+            ranges.join(", ")
+        }
+    };
+
+    let mut result = String::new();
+    if let Some(bounds) = coverage.bounds {
+        result += &format!("sections: {}-{}\n", bounds.start(), bounds.end());
+    }
+    result += &format!("uncovered: {}\n", list(&coverage.uncovered));
+    result += &format!(
+        "max overlap: {} at {}\n",
+        coverage.max_depth,
+        list(&coverage.max_depth_at)
+    );
+    result += &format!("redundant elves: {}\n", coverage.redundant_elves.len());
+    result += &format!("redundant groups: {}\n", coverage.redundant_groups.len());
+    result
 }
 
 #[cfg(test)]
@@ -73,13 +254,19 @@ mod tests {
     #[test]
     fn test_parse_range() {
         let range = "1-100";
-        assert_eq!(parse_range(range), (1, 100));
+        assert_eq!(parse_range(range), Some(Interval::new(1, 100)));
+        assert_eq!(parse_range("100-1"), None);
+        assert_eq!(parse_range("1-x"), None);
     }
 
     #[test]
     fn test_parse_line() {
         let line = "1-100,200-300";
-        assert_eq!(parse_line(line), ((1, 100), (200, 300)));
+        assert_eq!(
+            parse_line(line),
+            Some((Interval::new(1, 100), Interval::new(200, 300)))
+        );
+        assert_eq!(parse_line("1-100"), None);
     }
 
     #[test]
@@ -101,13 +288,107 @@ mod tests {
         assert_eq!(answer, 893);
     }
 
+    #[test]
+    fn test_parse_camp() {
+        let groups = parse_camp("1-3,5-7,2-2\n\n4-4").unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].assignments.len(), 3);
+        assert_eq!(groups[1].line, 3);
+        assert_eq!(groups[1].assignments, [Interval::new(4, 4)]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_camp("1-3\n\n5-4,6-7"),
+            Err(ParseError {
+                line: 3,
+                text: "5-4,6-7".to_string()
+            })
+        );
+        assert_eq!(
+            try_solve_part1("1-2,3-4\n2-3"),
+            Err(ParseError {
+                line: 2,
+                text: "2-3".to_string()
+            })
+        );
+        assert!(try_solve_part2("1-2,3").is_err());
+    }
+
+    #[test]
+    fn test_camp_coverage_sample() {
+        let input = r#"2-4,6-8
+        2-3,4-5
+        5-7,7-9
+        2-8,3-7
+        6-6,4-6
+        2-6,4-8"#;
+        let coverage = camp_coverage(&parse_camp(input).unwrap());
+
+        assert_eq!(coverage.bounds, Some(Interval::new(2, 9)));
+        assert!(coverage.uncovered.is_empty());
+        // 6 is in 2-4,6-8 / 5-7 / 2-8,3-7 / 6-6,4-6 / 2-6,4-8
+        assert_eq!(coverage.max_depth, 8);
+        assert_eq!(coverage.max_depth_at.intervals(), [Interval::new(6, 6)]);
+        assert_eq!(coverage.redundant_elves, [(4, 1), (5, 0)]);
+        // only 5-7,7-9 reaches section 9
+        assert_eq!(coverage.redundant_groups, [1, 2, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_camp_coverage() {
+        let coverage = camp_coverage(&parse_camp("1-3,2-5,4-6\n11-12\n11-11,12-20").unwrap());
+        assert_eq!(coverage.bounds, Some(Interval::new(1, 20)));
+        assert_eq!(coverage.uncovered.intervals(), [Interval::new(7, 10)]);
+        assert_eq!(coverage.max_depth, 2);
+        assert_eq!(
+            coverage.max_depth_at.intervals(),
+            [Interval::new(2, 5), Interval::new(11, 12)]
+        );
+        assert_eq!(coverage.redundant_elves, [(1, 1)]);
+        assert_eq!(coverage.redundant_groups, [2]);
+
+        assert_eq!(
+            render_coverage(&coverage),
+            "\
+sections: 1-20
+uncovered: 7-10
+max overlap: 2 at 2-5, 11-12
+redundant elves: 1
+redundant groups: 1
+"
+        );
+    }
+
+    #[test]
+    fn test_camp_coverage_input() {
+        let input = include_str!("../input/day_04.txt");
+        let groups = parse_camp(input).unwrap();
+        let coverage = camp_coverage(&groups);
+        assert_eq!(coverage.bounds, Some(Interval::new(1, 99)));
+        let contained = groups
+            .iter()
+            .filter(|group| {
+                coverage
+                    .redundant_elves
+                    .iter()
+                    .any(|&(line, _)| line == group.line)
+            })
+            .count();
+        assert_eq!(contained, 582);
+    }
+
     #[test]
     fn test_is_overlap() {
-        assert_eq!(is_overlap((1, 100), (50, 200)), true);
-        assert_eq!(is_overlap((1, 100), (101, 200)), false);
-        assert_eq!(is_overlap((1, 100), (0, 50)), true);
-        assert_eq!(is_overlap((1, 100), (100, 100)), true);
-        assert_eq!(is_overlap((1, 100), (100, 101)), true);
-        assert_eq!(is_overlap((1, 100), (0, 0)), false);
+        let overlap = |a: (u32, u32), b: (u32, u32)| {
+            Interval::new(a.0, a.1).overlaps(&Interval::new(b.0, b.1))
+        };
+        assert!(overlap((1, 100), (50, 200)));
+        assert!(!overlap((1, 100), (101, 200)));
+        assert!(overlap((1, 100), (0, 50)));
+        assert!(overlap((1, 100), (100, 100)));
+        assert!(overlap((1, 100), (100, 101)));
+        assert!(!overlap((1, 100), (0, 0)));
     }
 }
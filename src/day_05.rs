@@ -1,107 +1,836 @@
-use std::{collections::VecDeque, str::Lines};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt,
+    str::Lines,
+};
 
-pub fn solve_part1(input: &str) -> String {
-    let mut lines = input.lines();
-    let mut cargo_stack = load_cargo_stack(&mut lines);
-    let commands = parse_command(&mut lines);
+pub type Crate = String;
+
+/// Stacks in label order, each listed bottom to top.
+pub type Stacks = Vec<Vec<Crate>>;
 
-    for command in commands {
-        let Command::Move(count, from, to) = command;
+/// A crate as cranes move it: an index into the crate names, so bulk moves
+/// copy 4 bytes per crate instead of a whole `String`.
+pub type CrateId = u32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagramError {
+    MissingLabels,
+    InvalidLabel {
+        line: usize,
+        label: String,
+    },
+    LabelOrder {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    MalformedCrate {
+        line: usize,
+        column: usize,
+    },
+    UnlabeledCrate {
+        line: usize,
+        column: usize,
+    },
+    AmbiguousCrate {
+        line: usize,
+        column: usize,
+    },
+    FloatingCrate {
+        line: usize,
+        column: usize,
+    },
+}
 
-        for _ in 0..count {
-            let cargo = cargo_stack[from - 1].pop_back().unwrap();
-            cargo_stack[to - 1].push_back(cargo);
+impl fmt::Display for DiagramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagramError::MissingLabels => write!(f, "diagram has no stack label row"),
+            DiagramError::InvalidLabel { line, label } => {
+                write!(f, "line {line}: invalid stack label `{label}`")
+            }
+            DiagramError::LabelOrder {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: expected stack label {expected}, found {found}"
+            ),
+            DiagramError::MalformedCrate { line, column } => {
+                write!(f, "line {line}, column {column}: malformed crate")
+            }
+            DiagramError::UnlabeledCrate { line, column } => {
+                write!(
+                    f,
+                    "line {line}, column {column}: crate is not under any label"
+                )
+            }
+            DiagramError::AmbiguousCrate { line, column } => {
+                write!(
+                    f,
+                    "line {line}, column {column}: crate is under several labels"
+                )
+            }
+            DiagramError::FloatingCrate { line, column } => {
+                write!(
+                    f,
+                    "line {line}, column {column}: crate has nothing below it"
+                )
+            }
         }
     }
+}
 
-    cargo_stack
-        .iter()
-        .map(|stack| *stack.back().unwrap())
-        .collect()
+impl std::error::Error for DiagramError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CraneError {
+    NoSuchStack(usize), // stack label
+    NotEnoughCrates {
+        stack: usize,
+        needed: usize,
+        available: usize,
+    },
+    OutOfReach {
+        count: usize,
+        reach: usize,
+    },
 }
 
-pub fn solve_part2(input: &str) -> String {
+impl fmt::Display for CraneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CraneError::NoSuchStack(stack) => write!(f, "there is no stack {stack}"),
+            CraneError::NotEnoughCrates {
+                stack,
+                needed,
+                available,
+            } => write!(f, "stack {stack} has {available} crates, {needed} needed"),
+            CraneError::OutOfReach { count, reach } => {
+                write!(f, "can't move {count} crates, the crane reaches {reach}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CraneError {}
+
+/// How a crane carries out `move n from a to b`.
+pub trait Crane {
+    /// Whether a move of `count` crates is possible at all, so dry runs can
+    /// check it without moving anything.
+    fn check(&self, _count: usize) -> Result<(), CraneError> {
+        Ok(())
+    }
+
+    /// Moves the top `count` crates of stack `from` onto stack `to`, both
+    /// 0-based. The simulator has already checked that the stacks exist and
+    /// that `from` holds enough crates.
+    fn move_crates(
+        &self,
+        stacks: &mut [Vec<CrateId>],
+        count: usize,
+        from: usize,
+        to: usize,
+    ) -> Result<(), CraneError>;
+}
+
+/// Moves crates one at a time, so they land in reverse order. Simulated as one
+/// reversed bulk move.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn move_crates(
+        &self,
+        stacks: &mut [Vec<CrateId>],
+        count: usize,
+        from: usize,
+        to: usize,
+    ) -> Result<(), CraneError> {
+        if let Some((from, to)) = source_and_target(stacks, from, to) {
+            let start = from.len() - count;
+            let bottom = to.len();
+            to.extend(from.drain(start..));
+            to[bottom..].reverse();
+        }
+        Ok(())
+    }
+}
+
+/// Moves all crates at once, keeping their order.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn move_crates(
+        &self,
+        stacks: &mut [Vec<CrateId>],
+        count: usize,
+        from: usize,
+        to: usize,
+    ) -> Result<(), CraneError> {
+        if let Some((from, to)) = source_and_target(stacks, from, to) {
+            let start = from.len() - count;
+            to.extend(from.drain(start..));
+        }
+        Ok(())
+    }
+}
+
+// Both stacks of a move, or None when they're the same stack, in which case
+// every crane leaves it as it was.
+fn source_and_target<T>(
+    stacks: &mut [Vec<T>],
+    from: usize,
+    to: usize,
+) -> Option<(&mut Vec<T>, &mut Vec<T>)> {
+    let [from, to] = stacks.get_disjoint_mut([from, to]).ok()?;
+    Some((from, to))
+}
+
+/// Splits moves into lifts of at most `capacity` crates, each done by `crane`.
+#[derive(Debug, Copy, Clone)]
+pub struct LimitedCapacity<C> {
+    crane: C,
+    capacity: usize,
+}
+
+impl<C: Crane> LimitedCapacity<C> {
+    pub fn new(crane: C, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        LimitedCapacity { crane, capacity }
+    }
+}
+
+impl<C: Crane> Crane for LimitedCapacity<C> {
+    fn check(&self, count: usize) -> Result<(), CraneError> {
+        self.crane.check(count.min(self.capacity))
+    }
+
+    fn move_crates(
+        &self,
+        stacks: &mut [Vec<CrateId>],
+        count: usize,
+        from: usize,
+        to: usize,
+    ) -> Result<(), CraneError> {
+        let mut remaining = count;
+        while remaining > 0 {
+            let lift = remaining.min(self.capacity);
+            self.crane.move_crates(stacks, lift, from, to)?;
+            remaining -= lift;
+        }
+        Ok(())
+    }
+}
+
+/// `crane` that only reaches the top `reach` crates of a stack, so larger
+/// moves fail.
+#[derive(Debug, Copy, Clone)]
+pub struct TopReach<C> {
+    crane: C,
+    reach: usize,
+}
+
+impl<C: Crane> TopReach<C> {
+    pub fn new(crane: C, reach: usize) -> Self {
+        TopReach { crane, reach }
+    }
+}
+
+impl<C: Crane> Crane for TopReach<C> {
+    fn check(&self, count: usize) -> Result<(), CraneError> {
+        if count > self.reach {
+            return Err(CraneError::OutOfReach {
+                count,
+                reach: self.reach,
+            });
+        }
+        self.crane.check(count)
+    }
+
+    fn move_crates(
+        &self,
+        stacks: &mut [Vec<CrateId>],
+        count: usize,
+        from: usize,
+        to: usize,
+    ) -> Result<(), CraneError> {
+        self.check(count)?;
+        self.crane.move_crates(stacks, count, from, to)
+    }
+}
+
+/// Runs `commands` with `crane` and returns the final stacks.
+pub fn simulate(
+    crane: &(impl Crane + ?Sized),
+    stacks: Stacks,
+    commands: &[Command],
+) -> Result<Stacks, CraneError> {
+    run(crane, stacks, commands).map_err(|(_, error)| error)
+}
+
+// `simulate`, reporting which command failed
+fn run(
+    crane: &(impl Crane + ?Sized),
+    stacks: Stacks,
+    commands: &[Command],
+) -> Result<Stacks, (usize, CraneError)> {
+    let mut interned = Interned::default();
+    let mut compact = interned.intern(&stacks);
+    for (idx, &command) in commands.iter().enumerate() {
+        apply(crane, &mut compact, command).map_err(|error| (idx, error))?;
+    }
+    Ok(interned.resolve(&compact))
+}
+
+// crate names and the ids they were given
+#[derive(Default)]
+struct Interned {
+    names: Vec<Crate>,
+    ids: HashMap<Crate, CrateId>,
+}
+
+impl Interned {
+    fn intern(&mut self, stacks: &Stacks) -> Vec<Vec<CrateId>> {
+        stacks
+            .iter()
+            .map(|stack| {
+                stack
+                    .iter()
+                    .map(|name| {
+                        *self.ids.entry(name.clone()).or_insert_with_key(|name| {
+                            self.names.push(name.clone());
+                            self.names.len() as CrateId - 1
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn resolve(&self, stacks: &[Vec<CrateId>]) -> Stacks {
+        stacks
+            .iter()
+            .map(|stack| {
+                stack
+                    .iter()
+                    .map(|&id| self.names[id as usize].clone())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn apply(
+    crane: &(impl Crane + ?Sized),
+    stacks: &mut [Vec<CrateId>],
+    command: Command,
+) -> Result<(), CraneError> {
+    check_move(stacks.len(), |stack| stacks[stack].len(), command)?;
+    let Command::Move(count, from, to) = command;
+    crane.move_crates(stacks, count, from - 1, to - 1)
+}
+
+// the checks every crane needs, on stack heights only
+fn check_move(
+    stack_count: usize,
+    height: impl Fn(usize) -> usize,
+    Command::Move(count, from, to): Command,
+) -> Result<(), CraneError> {
+    for stack in [from, to] {
+        if stack == 0 || stack > stack_count {
+            return Err(CraneError::NoSuchStack(stack));
+        }
+    }
+    let available = height(from - 1);
+    if available < count {
+        return Err(CraneError::NotEnoughCrates {
+            stack: from,
+            needed: count,
+            available,
+        });
+    }
+    Ok(())
+}
+
+/// A command with the input line it came from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub line: usize, // 1-based
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcedureError {
+    Diagram(DiagramError),
+    Malformed { line: usize, text: String },
+    Invalid { line: usize, error: CraneError },
+}
+
+impl fmt::Display for ProcedureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcedureError::Diagram(err) => write!(f, "{err}"),
+            ProcedureError::Malformed { line, text } => {
+                write!(
+                    f,
+                    "line {line}: expected `move N from A to B`, got `{text}`"
+                )
+            }
+            ProcedureError::Invalid { line, error } => write!(f, "line {line}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ProcedureError {}
+
+impl From<DiagramError> for ProcedureError {
+    fn from(err: DiagramError) -> Self {
+        ProcedureError::Diagram(err)
+    }
+}
+
+/// The diagram and every command with its line number. A line that isn't a
+/// `move` command is an error.
+pub fn parse_procedure(input: &str) -> Result<(Stacks, Vec<Step>), ProcedureError> {
     let mut lines = input.lines();
-    let mut cargo_stack = load_cargo_stack(&mut lines);
-    let commands = parse_command(&mut lines);
+    let stacks = load_cargo_stack(&mut lines)?;
+    let first_line = input.lines().count() - lines.clone().count() + 1;
+    let steps = parse_steps(&mut lines, first_line)?;
+    Ok((stacks, steps))
+}
+
+fn parse_steps(lines: &mut Lines, first_line: usize) -> Result<Vec<Step>, ProcedureError> {
+    let mut steps = Vec::new();
+    for (idx, line) in lines.enumerate() {
+        let line_no = first_line + idx;
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let malformed = || ProcedureError::Malformed {
+            line: line_no,
+            text: text.to_string(),
+        };
+        let tokens: Vec<&str> = text.split_ascii_whitespace().collect();
+        let ["move", count, "from", from, "to", to] = tokens[..] else {
+            return Err(malformed());
+        };
+        let [count, from, to] = [count, from, to].map(str::parse::<usize>);
+        steps.push(Step {
+            line: line_no,
+            command: Command::Move(
+                count.map_err(|_| malformed())?,
+                from.map_err(|_| malformed())?,
+                to.map_err(|_| malformed())?,
+            ),
+        });
+    }
+    Ok(steps)
+}
+
+/// Validation pass: checks every step against the stack heights it would see,
+/// without moving any crates, and returns the final heights.
+pub fn dry_run(
+    crane: &(impl Crane + ?Sized),
+    stacks: &Stacks,
+    steps: &[Step],
+) -> Result<Vec<usize>, ProcedureError> {
+    let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+    for step in steps {
+        let invalid = |error| ProcedureError::Invalid {
+            line: step.line,
+            error,
+        };
+        check_move(heights.len(), |stack| heights[stack], step.command).map_err(invalid)?;
+        let Command::Move(count, from, to) = step.command;
+        crane.check(count).map_err(invalid)?;
+        heights[from - 1] -= count;
+        heights[to - 1] += count;
+    }
+    Ok(heights)
+}
+
+/// The stacks after every step of a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub initial: Stacks,
+    pub states: Vec<(Step, Stacks)>,
+}
+
+impl Replay {
+    pub fn last(&self) -> &Stacks {
+        self.states
+            .last()
+            .map_or(&self.initial, |(_, stacks)| stacks)
+    }
 
-    for command in commands {
-        let Command::Move(count, from, to) = command;
+    /// The initial diagram, then each command followed by the diagram after it.
+    pub fn render(&self) -> String {
+        let mut result = render_diagram(&self.initial);
+        for (step, stacks) in &self.states {
+            result += &format!("\n{}\n", step.command);
+            result += &render_diagram(stacks);
+        }
+        result
+    }
+}
+
+/// Runs `steps`, keeping a copy of the stacks after each one.
+pub fn replay(
+    crane: &(impl Crane + ?Sized),
+    stacks: Stacks,
+    steps: &[Step],
+) -> Result<Replay, ProcedureError> {
+    let mut interned = Interned::default();
+    let mut current = interned.intern(&stacks);
+    let mut states = Vec::with_capacity(steps.len());
+    for &step in steps {
+        apply(crane, &mut current, step.command).map_err(|error| ProcedureError::Invalid {
+            line: step.line,
+            error,
+        })?;
+        states.push((step, interned.resolve(&current)));
+    }
+    Ok(Replay {
+        initial: stacks,
+        states,
+    })
+}
+
+/// Draws `stacks` the way the puzzle input does, which `load_cargo_stack`
+/// reads back. Columns widen to fit the longest crate name or label.
+pub fn render_diagram(stacks: &Stacks) -> String {
+    let name_width = stacks.iter().flatten().map(String::len).max().unwrap_or(1);
+    let label_width = stacks.len().to_string().len();
+    let width = (name_width + 2).max(label_width);
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut rows = Vec::new();
+    for level in (0..height).rev() {
+        let cells: Vec<String> = stacks
+            .iter()
+            .map(|stack| match stack.get(level) {
+                Some(name) => format!("{:<width$}", format!("[{name}]")),
+                None => " ".repeat(width),
+            })
+            .collect();
+        rows.push(cells.join(" "));
+    }
+
+    let labels: Vec<String> = (1..=stacks.len())
+        .map(|label| {
+            let label = label.to_string();
+            let left = (width - label.len()) / 2;
+            format!("{}{label:<rest$}", " ".repeat(left), rest = width - left)
+        })
+        .collect();
+    rows.push(labels.join(" "));
+
+    rows.iter().map(|row| format!("{row}\n")).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    Diagram(DiagramError),
+    Mismatch, // the diagrams differ in stack count or crates
+    LimitReached { expanded: usize },
+    Unreachable,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::Diagram(err) => write!(f, "{err}"),
+            PlanError::Mismatch => write!(f, "start and target hold different crates"),
+            PlanError::LimitReached { expanded } => {
+                write!(f, "gave up after expanding {expanded} states")
+            }
+            PlanError::Unreachable => write!(f, "the crane can't reach the target"),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+impl From<DiagramError> for PlanError {
+    fn from(err: DiagramError) -> Self {
+        PlanError::Diagram(err)
+    }
+}
+
+/// Like `plan` with both arrangements given as diagrams.
+pub fn plan_diagrams(
+    crane: &(impl Crane + ?Sized),
+    start: &str,
+    target: &str,
+    limit: usize,
+) -> Result<Vec<Command>, PlanError> {
+    let start = load_cargo_stack(&mut start.lines())?;
+    let target = load_cargo_stack(&mut target.lines())?;
+    plan(crane, &start, &target, limit)
+}
+
+/// A shortest list of commands that turns `start` into `target` with `crane`,
+/// found by A*. Gives up after expanding `limit` states.
+pub fn plan(
+    crane: &(impl Crane + ?Sized),
+    start: &Stacks,
+    target: &Stacks,
+    limit: usize,
+) -> Result<Vec<Command>, PlanError> {
+    fn sorted(stacks: &Stacks) -> Vec<&Crate> {
+        let mut crates: Vec<&Crate> = stacks.iter().flatten().collect();
+        crates.sort();
+        crates
+    }
+    if start.len() != target.len() || sorted(start) != sorted(target) {
+        return Err(PlanError::Mismatch);
+    }
+    let mut interned = Interned::default();
+    let start = interned.intern(start);
+    let target = interned.intern(target);
+
+    // every state, with the state and command it was reached by
+    let mut nodes: Vec<(_, Option<(usize, Command)>)> = vec![(start.clone(), None)];
+    let mut best = HashMap::from([(start.clone(), 0)]);
+    // smallest f first, then the deepest, then the oldest
+    let mut open = BinaryHeap::from([(Reverse(plan_heuristic(&start, &target)), 0, Reverse(0))]);
+    let mut expanded = 0;
 
-        let mut temp = Vec::new();
-        for _ in 0..count {
-            let cargo = cargo_stack[from - 1].pop_back().unwrap();
-            temp.push(cargo);
+    while let Some((_, cost, Reverse(node))) = open.pop() {
+        let state = nodes[node].0.clone();
+        if best[&state] < cost {
+            continue;
+        }
+        if state == target {
+            let mut commands = Vec::new();
+            let mut node = node;
+            while let Some((parent, command)) = nodes[node].1 {
+                commands.push(command);
+                node = parent;
+            }
+            commands.reverse();
+            return Ok(commands);
         }
 
-        for cargo in temp.into_iter().rev() {
-            cargo_stack[to - 1].push_back(cargo);
+        expanded += 1;
+        if expanded > limit {
+            return Err(PlanError::LimitReached { expanded: limit });
+        }
+
+        for from in 0..state.len() {
+            for to in (0..state.len()).filter(|&to| to != from) {
+                for count in 1..=state[from].len() {
+                    if crane.check(count).is_err() {
+                        continue;
+                    }
+                    let mut next = state.clone();
+                    if crane.move_crates(&mut next, count, from, to).is_err() {
+                        continue;
+                    }
+                    if best.get(&next).is_some_and(|&seen| seen <= cost + 1) {
+                        continue;
+                    }
+
+                    let estimate = cost + 1 + plan_heuristic(&next, &target);
+                    best.insert(next.clone(), cost + 1);
+                    let command = Command::Move(count, from + 1, to + 1);
+                    nodes.push((next, Some((node, command))));
+                    open.push((Reverse(estimate), cost + 1, Reverse(nodes.len() - 1)));
+                }
+            }
         }
     }
 
-    cargo_stack
+    Err(PlanError::Unreachable)
+}
+
+// Every stack holding crates above what it shares with its target needs a move
+// out, every stack missing crates a move in, and a move has one of each.
+fn plan_heuristic(stacks: &[Vec<CrateId>], target: &[Vec<CrateId>]) -> usize {
+    let (mut outs, mut ins) = (0, 0);
+    for (stack, target) in stacks.iter().zip(target) {
+        let common = stack.iter().zip(target).take_while(|(a, b)| a == b).count();
+        outs += usize::from(stack.len() > common);
+        ins += usize::from(target.len() > common);
+    }
+    outs.max(ins)
+}
+
+/// `commands` one per line, as `parse_input` reads them after the diagram.
+pub fn render_commands(commands: &[Command]) -> String {
+    commands
         .iter()
-        .map(|stack| *stack.back().unwrap())
+        .map(|command| format!("{command}\n"))
         .collect()
 }
 
-fn get_cargo_stack_count(lines: &Lines) -> usize {
-    let char_count = lines.clone().next().unwrap().chars().count();
-    (char_count + 1) / 4
+/// `parse_procedure` without the line numbers.
+pub fn parse_input(input: &str) -> Result<(Stacks, Vec<Command>), ProcedureError> {
+    let (stacks, steps) = parse_procedure(input)?;
+    Ok((stacks, steps.iter().map(|step| step.command).collect()))
+}
+
+pub fn solve_part1(input: &str) -> String {
+    try_solve_part1(input).unwrap()
+}
+
+pub fn solve_part2(input: &str) -> String {
+    try_solve_part2(input).unwrap()
 }
 
-fn load_cargo_stack(lines: &mut Lines) -> VecDeque<VecDeque<char>> {
-    let stack_count = get_cargo_stack_count(lines);
+pub fn try_solve_part1(input: &str) -> Result<String, ProcedureError> {
+    solve_with(input, &CrateMover9000)
+}
 
-    let mut result = VecDeque::new();
-    result.resize(stack_count, VecDeque::new());
+pub fn try_solve_part2(input: &str) -> Result<String, ProcedureError> {
+    solve_with(input, &CrateMover9001)
+}
 
-    loop {
-        let Some(line) = lines.next() else {
-            break;
-        };
+pub fn solve_with(input: &str, crane: &impl Crane) -> Result<String, ProcedureError> {
+    let (stacks, steps) = parse_procedure(input)?;
+    let commands: Vec<Command> = steps.iter().map(|step| step.command).collect();
+    let stacks = run(crane, stacks, &commands).map_err(|(idx, error)| ProcedureError::Invalid {
+        line: steps[idx].line,
+        error,
+    })?;
+    Ok(top_crates(&stacks))
+}
+
+/// The top crate of every stack, empty stacks left out.
+pub fn top_crates(stacks: &Stacks) -> String {
+    stacks
+        .iter()
+        .filter_map(|stack| stack.last())
+        .map(String::as_str)
+        .collect()
+}
 
-        if line.chars().any(|c| c.is_numeric()) {
+/// Reads the diagram up to the first blank line. Crates are assigned to the
+/// stack whose label they sit above, so columns don't have to be 4 wide and
+/// trailing spaces don't matter.
+pub fn load_cargo_stack(lines: &mut Lines) -> Result<Stacks, DiagramError> {
+    let mut rows = Vec::new();
+    for line in lines.by_ref() {
+        if line.trim().is_empty() {
             break;
         }
+        rows.push(line);
+    }
 
-        // add existing crates
-        for (idx, c) in line.chars().enumerate() {
-            if c.is_alphabetic() {
-                let stack_idx = idx / 4;
-                result[stack_idx].push_front(c);
+    let (label_row, crate_rows) = rows.split_last().ok_or(DiagramError::MissingLabels)?;
+    if label_row.contains('[') {
+        return Err(DiagramError::MissingLabels);
+    }
+    let labels = parse_labels(label_row, rows.len())?;
+
+    let mut stacks = vec![Vec::new(); labels.len()];
+    // bottom row first, so every crate must land on the one below it
+    for (idx, row) in crate_rows.iter().enumerate().rev() {
+        let line = idx + 1;
+        let height = crate_rows.len() - 1 - idx;
+
+        for (name, start, end) in parse_crates(row, line)? {
+            let column = start + 1;
+            let mut under = labels
+                .iter()
+                .enumerate()
+                .filter(|(_, &(label_start, label_end))| label_start <= end && start <= label_end)
+                .map(|(stack, _)| stack);
+
+            let stack = under
+                .next()
+                .ok_or(DiagramError::UnlabeledCrate { line, column })?;
+            if under.next().is_some() || stacks[stack].len() > height {
+                return Err(DiagramError::AmbiguousCrate { line, column });
+            }
+            if stacks[stack].len() < height {
+                return Err(DiagramError::FloatingCrate { line, column });
             }
+            stacks[stack].push(name);
         }
     }
 
-    result
+    Ok(stacks)
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Command {
-    Move(usize, usize, usize),
+// column span of each label, which must read 1, 2, 3, ...
+fn parse_labels(row: &str, line: usize) -> Result<Vec<(usize, usize)>, DiagramError> {
+    let mut labels = Vec::new();
+    for (label, start, end) in tokens(row) {
+        let found: usize = label.parse().map_err(|_| DiagramError::InvalidLabel {
+            line,
+            label: label.to_string(),
+        })?;
+        let expected = labels.len() + 1;
+        if found != expected {
+            return Err(DiagramError::LabelOrder {
+                line,
+                expected,
+                found,
+            });
+        }
+        labels.push((start, end));
+    }
+    Ok(labels)
 }
 
-fn parse_command(lines: &mut Lines) -> Vec<Command> {
-    let mut commands = Vec::new();
+// `[name]` tokens with the column span of their brackets
+fn parse_crates(row: &str, line: usize) -> Result<Vec<(Crate, usize, usize)>, DiagramError> {
+    tokens(row)
+        .into_iter()
+        .map(|(token, start, end)| {
+            let name = token
+                .strip_prefix('[')
+                .and_then(|token| token.strip_suffix(']'))
+                .filter(|name| !name.is_empty() && !name.contains(['[', ']']))
+                .ok_or(DiagramError::MalformedCrate {
+                    line,
+                    column: start + 1,
+                })?;
+            Ok((name.to_string(), start, end))
+        })
+        .collect()
+}
 
-    for line in lines {
-        let mut split = line.trim().split_ascii_whitespace();
-        if split.clone().count() != 6 {
+// whitespace-separated tokens with their first and last column (0-based)
+fn tokens(row: &str) -> Vec<(&str, usize, usize)> {
+    let chars: Vec<(usize, char)> = row.char_indices().collect();
+    let mut result = Vec::new();
+    let mut column = 0;
+    while column < chars.len() {
+        if chars[column].1.is_whitespace() {
+            column += 1;
             continue;
         }
+        let start = column;
+        while column < chars.len() && !chars[column].1.is_whitespace() {
+            column += 1;
+        }
+        let byte_end = chars.get(column).map_or(row.len(), |&(byte, _)| byte);
+        result.push((&row[chars[start].0..byte_end], start, column - 1));
+    }
+    result
+}
 
-        let _mv = split.next().unwrap();
-        let count = split.next().unwrap().parse::<usize>().unwrap();
-        let _from = split.next().unwrap();
-        let from = split.next().unwrap().parse::<usize>().unwrap();
-        let _to = split.next().unwrap();
-        let to = split.next().unwrap().parse::<usize>().unwrap();
+/// `Move(count, from, to)` with 1-based stack labels.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Command {
+    Move(usize, usize, usize),
+}
 
-        commands.push(Command::Move(count, from, to));
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Command::Move(count, from, to) = self;
+        write!(f, "move {count} from {from} to {to}")
     }
-
-    commands
 }
 
 #[cfg(test)]
@@ -114,11 +843,67 @@ mod tests {
 [C] [D] [E]
  1   2   3"#;
         let mut lines = input.lines();
-        let cargo_stack = load_cargo_stack(&mut lines);
+        let cargo_stack = load_cargo_stack(&mut lines).unwrap();
         assert_eq!(cargo_stack.len(), 3);
-        assert_eq!(cargo_stack[0], ['C', 'A']);
-        assert_eq!(cargo_stack[1], ['D']);
-        assert_eq!(cargo_stack[2], ['E', 'B']);
+        assert_eq!(cargo_stack[0], ["C", "A"]);
+        assert_eq!(cargo_stack[1], ["D"]);
+        assert_eq!(cargo_stack[2], ["E", "B"]);
+    }
+
+    #[test]
+    fn test_load_wide_diagram() {
+        // eleven stacks, labels wider than one digit and names wider than one
+        // letter, with the trailing spaces stripped
+        let input = r#"                                         [Kr]
+[A]                                      [Xe] [Q]
+[B]  [C]  [D]  [E]  [F]  [G]  [H]  [I]  [Ar] [Ne]
+ 1    2    3    4    5    6    7    8    9   10   11
+"#;
+        let stacks = load_cargo_stack(&mut input.lines()).unwrap();
+        assert_eq!(stacks.len(), 11);
+        assert_eq!(stacks[0], ["B", "A"]);
+        assert_eq!(stacks[8], ["Ar", "Xe", "Kr"]);
+        assert_eq!(stacks[9], ["Ne", "Q"]);
+        assert!(stacks[10].is_empty());
+        assert_eq!(top_crates(&stacks), "ACDEFGHIKrQ");
+    }
+
+    #[test]
+    fn test_diagram_errors() {
+        let load = |input: &str| load_cargo_stack(&mut input.lines());
+        assert_eq!(load(""), Err(DiagramError::MissingLabels));
+        assert_eq!(load("[A]\n[B]"), Err(DiagramError::MissingLabels));
+        assert_eq!(
+            load("[A]\n 1  x"),
+            Err(DiagramError::InvalidLabel {
+                line: 2,
+                label: "x".to_string()
+            })
+        );
+        assert_eq!(
+            load("[A]\n 1   3"),
+            Err(DiagramError::LabelOrder {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            load("[A] [B\n 1   2"),
+            Err(DiagramError::MalformedCrate { line: 1, column: 5 })
+        );
+        assert_eq!(
+            load("[A]     [B]\n 1   2"),
+            Err(DiagramError::UnlabeledCrate { line: 1, column: 9 })
+        );
+        assert_eq!(
+            load("[LONG]\n 1  2"),
+            Err(DiagramError::AmbiguousCrate { line: 1, column: 1 })
+        );
+        assert_eq!(
+            load("    [A]\n[B]\n 1   2"),
+            Err(DiagramError::FloatingCrate { line: 1, column: 5 })
+        );
     }
 
     #[test]
@@ -127,10 +912,11 @@ mod tests {
         move 3 from 1 to 3"#;
 
         let mut lines = input.lines();
-        let commands = parse_command(&mut lines);
-        assert_eq!(commands.len(), 2);
-        assert_eq!(commands[0], Command::Move(1, 2, 1));
-        assert_eq!(commands[1], Command::Move(3, 1, 3));
+        let steps = parse_steps(&mut lines, 1).unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].command, Command::Move(1, 2, 1));
+        assert_eq!(steps[1].command, Command::Move(3, 1, 3));
+        assert_eq!(steps[1].line, 2);
     }
 
     #[test]
@@ -170,6 +956,463 @@ move 1 from 1 to 2"#;
         assert_eq!(answer, "MCD");
     }
 
+    const SAMPLE_INPUT: &str = r#"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2"#;
+
+    fn stacks(stacks: &[&[&str]]) -> Stacks {
+        stacks
+            .iter()
+            .map(|stack| stack.iter().map(|name| name.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_cranes() {
+        // crates A to E as ids 0 to 4
+        let start: Vec<Vec<CrateId>> = vec![(0..5).collect(), vec![]];
+        let run = |crane: &dyn Crane, count| {
+            let mut stacks = start.clone();
+            crane.move_crates(&mut stacks, count, 0, 1).map(|_| {
+                stacks[1]
+                    .iter()
+                    .map(|&id| char::from(b'A' + id as u8))
+                    .collect::<String>()
+            })
+        };
+
+        assert_eq!(run(&CrateMover9000, 5), Ok("EDCBA".to_string()));
+        assert_eq!(run(&CrateMover9001, 5), Ok("ABCDE".to_string()));
+        assert_eq!(
+            run(&LimitedCapacity::new(CrateMover9001, 2), 5),
+            Ok("DEBCA".to_string())
+        );
+        assert_eq!(
+            run(&TopReach::new(CrateMover9001, 3), 3),
+            Ok("CDE".to_string())
+        );
+        assert_eq!(
+            run(&TopReach::new(CrateMover9001, 3), 4),
+            Err(CraneError::OutOfReach { count: 4, reach: 3 })
+        );
+    }
+
+    #[test]
+    fn test_dyn_crane() {
+        // cranes picked at runtime work everywhere a crane is taken
+        let (start, steps) = parse_procedure(SAMPLE_INPUT).unwrap();
+        let commands: Vec<Command> = steps.iter().map(|step| step.command).collect();
+        let cranes: [Box<dyn Crane>; 3] = [
+            Box::new(CrateMover9000),
+            Box::new(LimitedCapacity::new(CrateMover9001, 1)),
+            Box::new(TopReach::new(CrateMover9001, 2)),
+        ];
+        let expected = simulate(&CrateMover9000, start.clone(), &commands);
+        for crane in &cranes[..2] {
+            let crane = crane.as_ref();
+            assert_eq!(simulate(crane, start.clone(), &commands), expected);
+            assert_eq!(
+                replay(crane, start.clone(), &steps).unwrap().last(),
+                expected.as_ref().unwrap()
+            );
+            assert!(dry_run(crane, &start, &steps).is_ok());
+        }
+
+        let crane = cranes[2].as_ref();
+        assert_eq!(
+            crane.check(3),
+            Err(CraneError::OutOfReach { count: 3, reach: 2 })
+        );
+        assert!(dry_run(crane, &start, &steps).is_err());
+        let target = expected.unwrap();
+        let planned = plan(crane, &start, &target, 100_000).unwrap();
+        assert_eq!(simulate(crane, start, &planned), Ok(target));
+    }
+
+    #[test]
+    fn test_simulate() {
+        let (start, commands) = parse_input(SAMPLE_INPUT).unwrap();
+        let end = simulate(&CrateMover9000, start.clone(), &commands).unwrap();
+        assert_eq!(end, stacks(&[&["C"], &["M"], &["P", "D", "N", "Z"]]));
+
+        // one crate per lift turns the 9001 into a 9000
+        let crane = LimitedCapacity::new(CrateMover9001, 1);
+        assert_eq!(simulate(&crane, start.clone(), &commands), Ok(end));
+
+        // the second command lifts three crates
+        let crane = TopReach::new(CrateMover9001, 2);
+        assert_eq!(
+            simulate(&crane, start.clone(), &commands),
+            Err(CraneError::OutOfReach { count: 3, reach: 2 })
+        );
+
+        let commands = [Command::Move(1, 2, 4)];
+        assert_eq!(
+            simulate(&CrateMover9000, start.clone(), &commands),
+            Err(CraneError::NoSuchStack(4))
+        );
+        let commands = [Command::Move(2, 3, 1)];
+        assert_eq!(
+            simulate(&CrateMover9000, start, &commands),
+            Err(CraneError::NotEnoughCrates {
+                stack: 3,
+                needed: 2,
+                available: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_solve_with() {
+        let input = include_str!("../input/day_05.txt");
+        let crane = LimitedCapacity::new(CrateMover9001, 1);
+        assert_eq!(solve_with(input, &crane), Ok("BSDMQFLSP".to_string()));
+        // the three crates go over as N, D and then Z
+        let crane = LimitedCapacity::new(CrateMover9001, 2);
+        assert_eq!(solve_with(SAMPLE_INPUT, &crane), Ok("MCZ".to_string()));
+
+        let input = SAMPLE_INPUT.replace("move 3 from 1 to 3", "move 3 from 1 to three");
+        assert!(matches!(
+            try_solve_part1(&input),
+            Err(ProcedureError::Malformed { line: 7, .. })
+        ));
+        let input = SAMPLE_INPUT.replace("move 3 from 1 to 3", "move 9 from 1 to 3");
+        assert_eq!(
+            try_solve_part2(&input),
+            Err(ProcedureError::Invalid {
+                line: 7,
+                error: CraneError::NotEnoughCrates {
+                    stack: 1,
+                    needed: 9,
+                    available: 3
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_procedure() {
+        let (stacks, steps) = parse_procedure(SAMPLE_INPUT).unwrap();
+        assert_eq!(stacks.len(), 3);
+        assert_eq!(
+            steps[0],
+            Step {
+                line: 6,
+                command: Command::Move(1, 2, 1)
+            }
+        );
+        assert_eq!(steps[3].line, 9);
+        assert_eq!(steps[3].command.to_string(), "move 1 from 1 to 2");
+
+        let input = format!("{SAMPLE_INPUT}\nmove 2 from x to 1");
+        assert_eq!(
+            parse_procedure(&input),
+            Err(ProcedureError::Malformed {
+                line: 10,
+                text: "move 2 from x to 1".to_string()
+            })
+        );
+        assert_eq!(
+            parse_procedure("[LONG]\n 1  2"),
+            Err(ProcedureError::Diagram(DiagramError::AmbiguousCrate {
+                line: 1,
+                column: 1
+            }))
+        );
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let (stacks, steps) = parse_procedure(SAMPLE_INPUT).unwrap();
+        assert_eq!(dry_run(&CrateMover9000, &stacks, &steps), Ok(vec![1, 1, 4]));
+        assert_eq!(
+            dry_run(&TopReach::new(CrateMover9001, 2), &stacks, &steps),
+            Err(ProcedureError::Invalid {
+                line: 7,
+                error: CraneError::OutOfReach { count: 3, reach: 2 }
+            })
+        );
+        // lifting two at a time keeps every lift within reach
+        let crane = LimitedCapacity::new(TopReach::new(CrateMover9001, 2), 2);
+        assert!(dry_run(&crane, &stacks, &steps).is_ok());
+
+        let input = format!("{SAMPLE_INPUT}\nmove 2 from 1 to 3");
+        let (stacks, steps) = parse_procedure(&input).unwrap();
+        let err = dry_run(&CrateMover9000, &stacks, &steps).unwrap_err();
+        assert_eq!(err.to_string(), "line 10: stack 1 has 1 crates, 2 needed");
+        // nothing was moved
+        assert_eq!(stacks[2], ["P"]);
+
+        let input = format!("{SAMPLE_INPUT}\nmove 1 from 1 to 4");
+        let (stacks, steps) = parse_procedure(&input).unwrap();
+        assert_eq!(
+            dry_run(&CrateMover9000, &stacks, &steps),
+            Err(ProcedureError::Invalid {
+                line: 10,
+                error: CraneError::NoSuchStack(4)
+            })
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let (stacks, steps) = parse_procedure(SAMPLE_INPUT).unwrap();
+        let replay = replay(&CrateMover9001, stacks, &steps).unwrap();
+        assert_eq!(replay.states.len(), 4);
+        assert_eq!(top_crates(replay.last()), "MCD");
+
+        let rendered = replay.render();
+        assert!(rendered.starts_with(
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\n"
+        ));
+        assert!(rendered.ends_with(
+            "\
+move 1 from 1 to 2
+        [D]
+        [N]
+        [Z]
+[M] [C] [P]
+ 1   2   3 
+"
+        ));
+    }
+
+    #[test]
+    fn test_render_diagram() {
+        let input = include_str!("../input/day_05.txt");
+        let diagram: String = input
+            .lines()
+            .take(9)
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let loaded = load_cargo_stack(&mut input.lines()).unwrap();
+        assert_eq!(render_diagram(&loaded), diagram);
+
+        let wide = stacks(&[&["Ar", "Kr"], &[], &["B"]]);
+        let rendered = render_diagram(&wide);
+        assert_eq!(rendered, "[Kr]          \n[Ar]      [B] \n 1    2    3  \n");
+        assert_eq!(load_cargo_stack(&mut rendered.lines()), Ok(wide));
+    }
+
+    #[test]
+    fn test_plan() {
+        let start = stacks(&[&["A", "B"], &[], &[]]);
+        let target = stacks(&[&[], &["B", "A"], &[]]);
+        assert_eq!(
+            plan(&CrateMover9000, &start, &target, 100),
+            Ok(vec![Command::Move(2, 1, 2)])
+        );
+        // the 9001 keeps the order, so the crates go over one by one
+        assert_eq!(
+            plan(&CrateMover9001, &start, &target, 100),
+            Ok(vec![Command::Move(1, 1, 2), Command::Move(1, 1, 2)])
+        );
+
+        let commands = plan(&CrateMover9001, &target, &start, 100).unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(
+            simulate(&CrateMover9001, target.clone(), &commands),
+            Ok(start.clone())
+        );
+
+        assert_eq!(plan(&CrateMover9000, &start, &start, 0), Ok(vec![]));
+        assert_eq!(
+            plan(
+                &CrateMover9000,
+                &start,
+                &stacks(&[&["A"], &["C"], &[]]),
+                100
+            ),
+            Err(PlanError::Mismatch)
+        );
+        assert_eq!(
+            plan(&TopReach::new(CrateMover9001, 0), &start, &target, 100),
+            Err(PlanError::Unreachable)
+        );
+    }
+
+    #[test]
+    fn test_plan_sample() {
+        let (start, commands) = parse_input(SAMPLE_INPUT).unwrap();
+        let target = simulate(&CrateMover9000, start.clone(), &commands).unwrap();
+
+        let planned = plan(&CrateMover9000, &start, &target, 100_000).unwrap();
+        assert!(planned.len() <= commands.len());
+        assert_eq!(
+            simulate(&CrateMover9000, start.clone(), &planned),
+            Ok(target.clone())
+        );
+
+        let text = render_commands(&planned);
+        let steps = parse_steps(&mut text.lines(), 1).unwrap();
+        let commands: Vec<Command> = steps.iter().map(|step| step.command).collect();
+        assert_eq!(commands, planned);
+
+        assert_eq!(
+            plan(&CrateMover9000, &start, &target, 1),
+            Err(PlanError::LimitReached { expanded: 1 })
+        );
+    }
+
+    #[test]
+    fn test_plan_diagrams() {
+        let start = "[A]\n[B] [C]\n 1   2   3";
+        let target = "        [A]\n        [B]\n    [C] [D]\n 1   2   3";
+        assert_eq!(
+            plan_diagrams(&CrateMover9001, start, target, 100),
+            Err(PlanError::Mismatch)
+        );
+
+        // C has to get under A and B, which takes three moves
+        let target = "    [A]\n[C] [B]\n 1   2   3";
+        let commands = plan_diagrams(&CrateMover9001, start, target, 1000).unwrap();
+        assert_eq!(
+            render_commands(&commands),
+            "move 2 from 1 to 2\nmove 3 from 2 to 1\nmove 2 from 1 to 2\n"
+        );
+    }
+
+    // the crane models as they were before moves were done in bulk
+    struct OneByOne9000;
+
+    impl Crane for OneByOne9000 {
+        fn move_crates(
+            &self,
+            stacks: &mut [Vec<CrateId>],
+            count: usize,
+            from: usize,
+            to: usize,
+        ) -> Result<(), CraneError> {
+            for _ in 0..count {
+                let cargo = stacks[from].pop().unwrap();
+                stacks[to].push(cargo);
+            }
+            Ok(())
+        }
+    }
+
+    struct TempVec9001;
+
+    impl Crane for TempVec9001 {
+        fn move_crates(
+            &self,
+            stacks: &mut [Vec<CrateId>],
+            count: usize,
+            from: usize,
+            to: usize,
+        ) -> Result<(), CraneError> {
+            let mut temp = Vec::new();
+            for _ in 0..count {
+                temp.push(stacks[from].pop().unwrap());
+            }
+            for cargo in temp.into_iter().rev() {
+                stacks[to].push(cargo);
+            }
+            Ok(())
+        }
+    }
+
+    // A valid puzzle input with `stack_count` stacks of `height` crates and
+    // `command_count` moves of up to `max_move` crates.
+    fn generate_input(
+        stack_count: usize,
+        height: usize,
+        command_count: usize,
+        max_move: usize,
+    ) -> String {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % bound as u64) as usize
+        };
+
+        let stacks: Stacks = (0..stack_count)
+            .map(|_| {
+                (0..height)
+                    .map(|_| char::from(b'A' + random(26) as u8).to_string())
+                    .collect()
+            })
+            .collect();
+        let mut input = render_diagram(&stacks);
+        input += "\n";
+
+        let mut heights = vec![height; stack_count];
+        for _ in 0..command_count {
+            let from = loop {
+                let from = random(stack_count);
+                if heights[from] > 0 {
+                    break from;
+                }
+            };
+            let to = (from + 1 + random(stack_count - 1)) % stack_count;
+            let count = 1 + random(heights[from].min(max_move));
+            heights[from] -= count;
+            heights[to] += count;
+            input += &format!("{}\n", Command::Move(count, from + 1, to + 1));
+        }
+        input
+    }
+
+    #[test]
+    fn test_bulk_moves() {
+        let input = generate_input(5, 50, 2000, 40);
+        let (stacks, commands) = parse_input(&input).unwrap();
+        assert_eq!(commands.len(), 2000);
+
+        let expected = simulate(&OneByOne9000, stacks.clone(), &commands).unwrap();
+        assert_eq!(
+            simulate(&CrateMover9000, stacks.clone(), &commands),
+            Ok(expected)
+        );
+        let expected = simulate(&TempVec9001, stacks.clone(), &commands).unwrap();
+        assert_eq!(simulate(&CrateMover9001, stacks, &commands), Ok(expected));
+    }
+
+    // moves average 250 crates, so bulk moves should win by far more than the
+    // factor of 2 checked here, which leaves room for a noisy machine
+    // cargo test --release day_05::tests::bench_bulk_moves -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_bulk_moves() {
+        use std::time::Instant;
+
+        let input = generate_input(9, 5000, 1_000_000, 500);
+        let (stacks, commands) = parse_input(&input).unwrap();
+
+        let time = |crane: &dyn Crane| {
+            let start = Instant::now();
+            let end = simulate(crane, stacks.clone(), &commands).unwrap();
+            (start.elapsed(), top_crates(&end))
+        };
+
+        for (name, naive, bulk) in [
+            (
+                "9000",
+                &OneByOne9000 as &dyn Crane,
+                &CrateMover9000 as &dyn Crane,
+            ),
+            ("9001", &TempVec9001, &CrateMover9001),
+        ] {
+            let (naive, expected) = time(naive);
+            let (bulk, answer) = time(bulk);
+            let speedup = naive.as_secs_f64() / bulk.as_secs_f64();
+            println!("{name}: naive {naive:?}, bulk {bulk:?}, {speedup:.1}x faster");
+            assert_eq!(answer, expected);
+            assert!(
+                speedup > 2.0,
+                "{name}: bulk moves are only {speedup:.1}x faster"
+            );
+        }
+    }
+
     #[test]
     fn test_part2() {
         let input = include_str!("../input/day_05.txt");
@@ -1,32 +1,251 @@
-pub fn solve_part1(input: &str) -> u32 {
-    solve::<4>(input)
+use std::{
+    io::{self, Read},
+    ops::Range,
+};
+
+/// `None` when the stream has no start-of-packet marker.
+pub fn solve_part1(input: &str) -> Option<usize> {
+    first_marker(input.trim().as_bytes(), 4).expect("reading a slice can't fail")
+}
+
+/// `None` when the stream has no start-of-message marker.
+pub fn solve_part2(input: &str) -> Option<usize> {
+    first_marker(input.trim().as_bytes(), 14).expect("reading a slice can't fail")
 }
 
-pub fn solve_part2(input: &str) -> u32 {
-    solve::<14>(input)
+/// Sliding window over a byte stream that knows in O(1) per byte whether the
+/// last `window` bytes are all different (or, with a threshold, at least that
+/// many are).
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    window: Vec<u8>,
+    threshold: usize,
+    counts: [u32; 256],
+    distinct: usize,
+    seen: usize, // since the last reset
+    position: usize,
 }
 
-fn solve<const LEN: usize>(input: &str) -> u32 {
-    let mut last_4 = [0_u8; LEN];
-    for (idx, &c) in input.as_bytes().iter().enumerate() {
-        last_4[idx % LEN] = c;
+impl MarkerDetector {
+    pub fn new(window: usize) -> Self {
+        MarkerDetector::with_threshold(window, window)
+    }
+
+    /// Markers are windows with at least `threshold` different bytes.
+    pub fn with_threshold(window: usize, threshold: usize) -> Self {
+        assert!(window > 0, "window must hold at least one byte");
+        assert!(
+            (1..=window.min(256)).contains(&threshold),
+            "threshold must be between 1 and the window size"
+        );
+        MarkerDetector {
+            window: vec![0; window],
+            threshold,
+            counts: [0; 256],
+            distinct: 0,
+            seen: 0,
+            position: 0,
+        }
+    }
+
+    /// Empties the window, so the next marker can't share bytes with the last.
+    pub fn reset(&mut self) {
+        self.counts = [0; 256];
+        self.distinct = 0;
+        self.seen = 0;
+    }
+
+    pub fn window(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Bytes seen so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of different bytes in the window.
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
 
-        if idx >= LEN - 1 && is_unique(&last_4) {
-            return idx as u32 + 1;
+    /// Adds `byte` and returns whether the window now ends a marker.
+    pub fn push(&mut self, byte: u8) -> bool {
+        let slot = self.seen % self.window.len();
+        if self.seen >= self.window.len() {
+            let old = self.window[slot] as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 0 {
+                self.distinct -= 1;
+            }
         }
+
+        self.window[slot] = byte;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+        self.seen += 1;
+        self.position += 1;
+
+        self.seen >= self.window.len() && self.distinct >= self.threshold
     }
-    0
 }
 
-fn is_unique(slice: &[u8]) -> bool {
-    for i in 0..slice.len() {
-        for j in i + 1..slice.len() {
-            if slice[i] == slice[j] {
-                return false;
+/// Every position (bytes read so far) at which the last `window` bytes were
+/// all different, read from `reader` in chunks.
+pub struct Markers<R> {
+    reader: R,
+    detector: MarkerDetector,
+    buffer: Box<[u8]>,
+    filled: usize,
+    consumed: usize,
+}
+
+pub fn markers<R: Read>(reader: R, window: usize) -> Markers<R> {
+    Markers {
+        reader,
+        detector: MarkerDetector::new(window),
+        buffer: vec![0; 8192].into_boxed_slice(),
+        filled: 0,
+        consumed: 0,
+    }
+}
+
+impl<R: Read> Iterator for Markers<R> {
+    type Item = io::Result<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while self.consumed < self.filled {
+                let byte = self.buffer[self.consumed];
+                self.consumed += 1;
+                if self.detector.push(byte) {
+                    return Some(Ok(self.detector.position()));
+                }
             }
+
+            self.consumed = 0;
+            self.filled = match self.reader.read(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(filled) => filled,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => 0,
+                Err(err) => return Some(Err(err)),
+            };
         }
     }
-    true
+}
+
+/// Position just past the first marker of `window` different bytes.
+pub fn first_marker<R: Read>(reader: R, window: usize) -> io::Result<Option<usize>> {
+    markers(reader, window).next().transpose()
+}
+
+/// What counts as a marker: `window` bytes with at least `min_distinct`
+/// different ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerRule {
+    pub name: String,
+    pub window: usize,
+    pub min_distinct: usize,
+}
+
+impl MarkerRule {
+    pub fn new(name: &str, window: usize, min_distinct: usize) -> Self {
+        MarkerRule {
+            name: name.to_string(),
+            window,
+            min_distinct,
+        }
+    }
+
+    pub fn start_of_packet() -> Self {
+        MarkerRule::new("packet", 4, 4)
+    }
+
+    pub fn start_of_message() -> Self {
+        MarkerRule::new("message", 14, 14)
+    }
+
+    // what `MarkerDetector::with_threshold` accepts
+    fn is_valid(&self) -> bool {
+        self.window > 0 && (1..=self.window.min(256)).contains(&self.min_distinct)
+    }
+}
+
+/// The bytes from the end of one marker up to the start of the next marker of
+/// the same rule, or the end of the stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub rule: usize, // index into the decoder's rules
+    pub marker: Range<usize>,
+    pub payload: Range<usize>,
+    pub data: Vec<u8>,
+}
+
+/// Splits a stream into frames for every rule at once. After a marker the
+/// rule starts over, so markers of one rule never overlap. Bytes before a
+/// rule's first marker belong to no frame. A rule with an empty window, or
+/// asking for no or more distinct bytes than the window (or a byte) can hold,
+/// is an `InvalidInput` error.
+pub fn decode<R: Read>(mut reader: R, rules: &[MarkerRule]) -> io::Result<Vec<Frame>> {
+    if let Some(rule) = rules.iter().find(|rule| !rule.is_valid()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "rule `{}`: {} distinct bytes in a window of {}",
+                rule.name, rule.min_distinct, rule.window
+            ),
+        ));
+    }
+    let mut detectors: Vec<MarkerDetector> = rules
+        .iter()
+        .map(|rule| MarkerDetector::with_threshold(rule.window, rule.min_distinct))
+        .collect();
+    let mut open: Vec<Option<Frame>> = vec![None; rules.len()];
+    let mut frames = Vec::new();
+
+    let mut buffer = [0; 8192];
+    loop {
+        let filled = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(filled) => filled,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        for &byte in &buffer[..filled] {
+            for (rule, detector) in detectors.iter_mut().enumerate() {
+                if let Some(frame) = &mut open[rule] {
+                    frame.data.push(byte);
+                    frame.payload.end += 1;
+                }
+                if !detector.push(byte) {
+                    continue;
+                }
+
+                let end = detector.position();
+                let start = end - detector.window();
+                // the marker was counted into the frame it ends
+                if let Some(mut frame) = open[rule].take() {
+                    frame.data.truncate(frame.data.len() - detector.window());
+                    frame.payload.end = start;
+                    frames.push(frame);
+                }
+                open[rule] = Some(Frame {
+                    rule,
+                    marker: start..end,
+                    payload: end..end,
+                    data: Vec::new(),
+                });
+                detector.reset();
+            }
+        }
+    }
+
+    frames.extend(open.into_iter().flatten());
+    frames.sort_by_key(|frame| (frame.marker.end, frame.rule));
+    Ok(frames)
 }
 
 #[cfg(test)]
@@ -35,33 +254,159 @@ mod tests {
 
     #[test]
     fn test_part1_sample() {
-        assert_eq!(solve_part1("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), 7);
-        assert_eq!(solve_part1("bvwbjplbgvbhsrlpgdmjqwftvncz"), 5);
-        assert_eq!(solve_part1("nppdvjthqldpwncqszvftbrmjlhg"), 6);
-        assert_eq!(solve_part1("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), 10);
-        assert_eq!(solve_part1("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 11);
+        assert_eq!(solve_part1("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), Some(7));
+        assert_eq!(solve_part1("bvwbjplbgvbhsrlpgdmjqwftvncz"), Some(5));
+        assert_eq!(solve_part1("nppdvjthqldpwncqszvftbrmjlhg"), Some(6));
+        assert_eq!(solve_part1("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), Some(10));
+        assert_eq!(solve_part1("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), Some(11));
+        assert_eq!(solve_part1("abcabc"), None);
     }
 
     #[test]
     fn test_part1() {
         let input = include_str!("../input/day_06.txt");
         let answer = solve_part1(input);
-        assert_eq!(answer, 1651);
+        assert_eq!(answer, Some(1651));
     }
 
     #[test]
     fn test_part2_sample() {
-        assert_eq!(solve_part2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), 19);
-        assert_eq!(solve_part2("bvwbjplbgvbhsrlpgdmjqwftvncz"), 23);
-        assert_eq!(solve_part2("nppdvjthqldpwncqszvftbrmjlhg"), 23);
-        assert_eq!(solve_part2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), 29);
-        assert_eq!(solve_part2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 26);
+        assert_eq!(solve_part2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), Some(19));
+        assert_eq!(solve_part2("bvwbjplbgvbhsrlpgdmjqwftvncz"), Some(23));
+        assert_eq!(solve_part2("nppdvjthqldpwncqszvftbrmjlhg"), Some(23));
+        assert_eq!(solve_part2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), Some(29));
+        assert_eq!(solve_part2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), Some(26));
+    }
+
+    #[test]
+    fn test_detector() {
+        let mut detector = MarkerDetector::new(3);
+        let found: Vec<bool> = b"aabcb".iter().map(|&b| detector.push(b)).collect();
+        assert_eq!(found, [false, false, false, true, false]);
+        assert_eq!(detector.position(), 5);
+        assert_eq!(detector.distinct(), 2);
+    }
+
+    #[test]
+    fn test_first_marker() {
+        let stream = "mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes();
+        assert_eq!(first_marker(stream, 4).unwrap(), Some(7));
+        assert_eq!(first_marker(stream, 14).unwrap(), Some(19));
+        assert_eq!(first_marker(stream, 1).unwrap(), Some(1));
+        assert_eq!(first_marker("aaaa".as_bytes(), 2).unwrap(), None);
+        assert_eq!(first_marker("abc".as_bytes(), 4).unwrap(), None);
+    }
+
+    #[test]
+    fn test_markers() {
+        let found: Vec<usize> = markers("abcabbcd".as_bytes(), 3)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(found, [3, 4, 5, 8]);
+
+        // a reader that hands out one byte per call
+        struct Trickle<'a>(&'a [u8]);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let n = self.0.len().min(buf.len()).min(1);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+        let input = include_str!("../input/day_06.txt");
+        let first = markers(Trickle(input.as_bytes()), 14).next();
+        assert_eq!(first.unwrap().unwrap(), 3837);
+    }
+
+    #[test]
+    fn test_threshold() {
+        let mut detector = MarkerDetector::with_threshold(4, 3);
+        let found: Vec<bool> = b"aabcdd".iter().map(|&b| detector.push(b)).collect();
+        assert_eq!(found, [false, false, false, true, true, true]);
+
+        detector.reset();
+        assert!(!detector.push(b'x'));
+        assert_eq!(detector.position(), 7);
+    }
+
+    #[test]
+    fn test_decode() {
+        let rules = [MarkerRule::new("pair", 2, 2)];
+        let frames = decode("aabxxcdyy".as_bytes(), &rules).unwrap();
+        let summary: Vec<_> = frames
+            .iter()
+            .map(|f| (f.marker.clone(), f.payload.clone(), f.data.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1..3, 3..4, &b"x"[..]),
+                (4..6, 6..6, &b""[..]),
+                (6..8, 8..9, &b"y"[..]),
+            ]
+        );
+
+        let rules = [MarkerRule::new("loose", 4, 3)];
+        let frames = decode("aabcdd".as_bytes(), &rules).unwrap();
+        assert_eq!(
+            frames,
+            [Frame {
+                rule: 0,
+                marker: 0..4,
+                payload: 4..6,
+                data: b"dd".to_vec(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_rules() {
+        for (window, min_distinct) in [(0, 0), (0, 1), (4, 0), (4, 5), (300, 257)] {
+            let rules = [
+                MarkerRule::start_of_packet(),
+                MarkerRule::new("bad", window, min_distinct),
+            ];
+            let err = decode("abcd".as_bytes(), &rules).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(
+                err.to_string(),
+                format!("rule `bad`: {min_distinct} distinct bytes in a window of {window}")
+            );
+        }
+        assert!(decode("abcd".as_bytes(), &[MarkerRule::new("wide", 300, 256)]).is_ok());
+    }
+
+    #[test]
+    fn test_decode_packets_and_messages() {
+        let input = include_str!("../input/day_06.txt");
+        let rules = [
+            MarkerRule::start_of_packet(),
+            MarkerRule::start_of_message(),
+        ];
+        let frames = decode(input.as_bytes(), &rules).unwrap();
+
+        let first = |rule| frames.iter().find(|f| f.rule == rule).unwrap();
+        assert_eq!(first(0).marker.end, 1651);
+        assert_eq!(first(1).marker.end, 3837);
+
+        // frames of each rule tile the stream after their first marker
+        for rule in 0..rules.len() {
+            let mut frames = frames.iter().filter(|f| f.rule == rule).peekable();
+            while let Some(frame) = frames.next() {
+                assert_eq!(frame.data, input.as_bytes()[frame.payload.clone()]);
+                match frames.peek() {
+                    Some(next) => assert_eq!(frame.payload.end, next.marker.start),
+                    None => assert_eq!(frame.payload.end, input.len()),
+                }
+            }
+        }
     }
 
     #[test]
     fn test_part2() {
         let input = include_str!("../input/day_06.txt");
         let answer = solve_part2(input);
-        assert_eq!(answer, 3837);
+        assert_eq!(answer, Some(3837));
     }
 }
@@ -1,91 +1,914 @@
-use std::str::Lines;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, VecDeque},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
 
-pub fn solve_part1(input: &str) -> u32 {
-    let mut lines = input.lines();
-    lines.next(); // skip dir
+use crate::{
+    config::{Config, ConfigError},
+    overflow::{self, OverflowError},
+};
 
-    let mut sum_atmost = 0;
-    let _sum = get_directory_size(&mut lines, &mut sum_atmost, &mut 0, 0);
-    sum_atmost
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub small_dir_limit: u64,
+    pub total_space: u64,
+    pub need_space: u64,
 }
 
-pub fn solve_part2(input: &str) -> u32 {
-    let mut lines = input.lines();
-    lines.next(); // skip dir
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            small_dir_limit: 100_000,
+            total_space: 70000000,
+            need_space: 30000000,
+        }
+    }
+}
 
-    // pass 1 for sum total space
-    let mut _sum_atmost = 0;
-    let sum = get_directory_size(&mut lines, &mut _sum_atmost, &mut 0, 0);
-    let cur_space = 70000000 - sum;
-    let need_space = 30000000 - cur_space;
+impl Params {
+    /// Every config key `from_config` reads.
+    pub const KEYS: &'static [&'static str] = &[
+        "day_07.small_dir_limit",
+        "day_07.total_space",
+        "day_07.need_space",
+    ];
 
-    // pass 2 for smallest space that can fit needed space
-    let mut lines = input.lines();
-    lines.next(); // skip dir
-    let mut smallest = std::u32::MAX;
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let default = Params::default();
+        Ok(Params {
+            small_dir_limit: config.get_or("day_07.small_dir_limit", default.small_dir_limit)?,
+            total_space: config.get_or("day_07.total_space", default.total_space)?,
+            need_space: config.get_or("day_07.need_space", default.need_space)?,
+        })
+    }
+}
 
-    let _sum = get_directory_size(&mut lines, &mut _sum_atmost, &mut smallest, need_space);
+pub fn solve_part1(input: &str) -> u64 {
+    solve_part1_with(input, &Params::default())
+}
 
-    smallest
+pub fn solve_part2(input: &str) -> u64 {
+    solve_part2_with(input, &Params::default())
 }
 
-fn get_directory_size<'a>(
-    lines: &'a mut Lines,
-    sum_atmost: &mut u32,
-    smallest: &mut u32,
-    need_space: u32,
-) -> u32 {
-    let mut sum = 0;
-    while let Some(line) = lines.next() {
-        let (cmd1, cmd2, cmd3) = parse_line(line);
-        match (cmd1, cmd2, cmd3) {
-            ("$", "ls", _) => {}
-            ("$", "cd", Some("..")) => {
-                break;
+pub fn solve_part1_with(input: &str, params: &Params) -> u64 {
+    try_solve_part1(input, params).unwrap()
+}
+
+pub fn solve_part2_with(input: &str, params: &Params) -> u64 {
+    try_solve_part2(input, params).unwrap()
+}
+
+pub fn try_solve_part1(input: &str, params: &Params) -> Result<u64, SolveError> {
+    let fs = FileSystem::parse(input)?;
+    let sizes = fs.dir_sizes()?;
+
+    let mut sum_atmost = 0_u64;
+    for dir in fs.directories() {
+        if sizes[dir] <= params.small_dir_limit {
+            sum_atmost = overflow::add(sum_atmost, sizes[dir], 7, "sum of small directories")?;
+        }
+    }
+    Ok(sum_atmost)
+}
+
+/// Size of the smallest directory that frees enough space, 0 when there is
+/// enough free space already.
+pub fn try_solve_part2(input: &str, params: &Params) -> Result<u64, SolveError> {
+    let fs = FileSystem::parse(input)?;
+    let usage = fs.disk_usage()?;
+    let plan = usage.plan_single(params.total_space, params.need_space)?;
+    Ok(plan.deleted)
+}
+
+/// Why a part has no answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Transcript(TranscriptError),
+    Overflow(OverflowError),
+    Plan(PlanError),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Transcript(err) => write!(f, "{err}"),
+            SolveError::Overflow(err) => write!(f, "{err}"),
+            SolveError::Plan(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<TranscriptError> for SolveError {
+    fn from(err: TranscriptError) -> Self {
+        SolveError::Transcript(err)
+    }
+}
+
+impl From<OverflowError> for SolveError {
+    fn from(err: OverflowError) -> Self {
+        SolveError::Overflow(err)
+    }
+}
+
+impl From<PlanError> for SolveError {
+    fn from(err: PlanError) -> Self {
+        match err {
+            PlanError::Overflow(err) => SolveError::Overflow(err),
+            err => SolveError::Plan(err),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptError {
+    UnknownCommand { line: usize, text: String },
+    UnexpectedOutput { line: usize, text: String }, // listing outside of `ls`
+    MalformedEntry { line: usize, text: String },
+    Conflict { line: usize, path: String }, // both a file and a directory
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptError::UnknownCommand { line, text } => {
+                write!(f, "line {line}: unknown command `{text}`")
             }
-            ("$", "cd", Some(_dir)) => {
-                let sub_sum = get_directory_size(lines, sum_atmost, smallest, need_space);
-                sum += sub_sum;
+            TranscriptError::UnexpectedOutput { line, text } => {
+                write!(f, "line {line}: output `{text}` without `ls`")
             }
-            ("dir", _dir, _) => {
-                continue;
+            TranscriptError::MalformedEntry { line, text } => {
+                write!(f, "line {line}: malformed listing entry `{text}`")
+            }
+            TranscriptError::Conflict { line, path } => {
+                write!(f, "line {line}: `{path}` is both a file and a directory")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+pub type NodeId = usize;
+
+pub const ROOT: NodeId = 0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+    Dir(BTreeMap<String, NodeId>),
+    File(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: NodeKind,
+}
+
+/// Directory tree rebuilt from a `$ cd` / `$ ls` transcript. Nodes live in
+/// one arena and a node is always created after its parent, so children have
+/// larger ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem {
+            nodes: vec![Node {
+                name: String::new(),
+                parent: None,
+                kind: NodeKind::Dir(BTreeMap::new()),
+            }],
+        }
+    }
+}
+
+impl FileSystem {
+    pub fn new() -> Self {
+        FileSystem::default()
+    }
+
+    /// Replays the transcript. `cd` may jump anywhere (`/`, `..`, a path, or
+    /// a directory not listed yet), directories may be listed more than once
+    /// and the log may stop at any depth.
+    pub fn parse(input: &str) -> Result<Self, TranscriptError> {
+        let mut fs = FileSystem::new();
+        let mut cwd = ROOT;
+        let mut listing = false;
+
+        for (idx, line) in input.lines().enumerate() {
+            let line_no = idx + 1;
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["$", "cd", target] => {
+                    cwd = fs
+                        .change_dir(cwd, target)
+                        .map_err(|path| TranscriptError::Conflict {
+                            line: line_no,
+                            path,
+                        })?;
+                }
+                ["$", "ls"] => {}
+                ["$", ..] => {
+                    return Err(TranscriptError::UnknownCommand {
+                        line: line_no,
+                        text: line.to_string(),
+                    })
+                }
+                _ if !listing => {
+                    return Err(TranscriptError::UnexpectedOutput {
+                        line: line_no,
+                        text: line.to_string(),
+                    })
+                }
+                ["dir", name] => {
+                    fs.dir_entry(cwd, name)
+                        .ok_or_else(|| TranscriptError::Conflict {
+                            line: line_no,
+                            path: fs.child_path(cwd, name),
+                        })?;
+                }
+                [size, name] => {
+                    let size = size.parse().map_err(|_| TranscriptError::MalformedEntry {
+                        line: line_no,
+                        text: line.to_string(),
+                    })?;
+                    fs.file_entry(cwd, name, size)
+                        .ok_or_else(|| TranscriptError::Conflict {
+                            line: line_no,
+                            path: fs.child_path(cwd, name),
+                        })?;
+                }
+                _ => {
+                    return Err(TranscriptError::MalformedEntry {
+                        line: line_no,
+                        text: line.to_string(),
+                    })
+                }
+            }
+            if words.first() == Some(&"$") {
+                listing = words.get(1) == Some(&"ls");
+            }
+        }
+        Ok(fs)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 1
+    }
+
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Dir(_))
+    }
+
+    /// Children of a directory by name, nothing for a file.
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let children = match &self.nodes[id].kind {
+            NodeKind::Dir(children) => Some(children.values().copied()),
+            NodeKind::File(_) => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|&id| self.is_dir(id))
+    }
+
+    /// Absolute path, `/` for the root.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = Vec::new();
+        let mut node = id;
+        while let Some(parent) = self.nodes[node].parent {
+            names.push(self.nodes[node].name.as_str());
+            node = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    pub fn lookup(&self, path: &str) -> Option<NodeId> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(ROOT, |dir, name| match &self.nodes[dir].kind {
+                NodeKind::Dir(children) => children.get(name).copied(),
+                NodeKind::File(_) => None,
+            })
+    }
+
+    /// Every node with its depth below the root, depth-first in name order.
+    pub fn walk(&self) -> Vec<(NodeId, usize)> {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![(ROOT, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            order.push((id, depth));
+            let children: Vec<NodeId> = self.children(id).collect();
+            stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+        order
+    }
+
+    /// Sizes of every node, ready for queries.
+    pub fn disk_usage(&self) -> Result<DiskUsage<'_>, OverflowError> {
+        Ok(DiskUsage {
+            fs: self,
+            sizes: self.dir_sizes()?,
+        })
+    }
+
+    /// Total size below every node, indexed by id.
+    pub fn dir_sizes(&self) -> Result<Vec<u64>, OverflowError> {
+        let mut sizes: Vec<u64> = self
+            .nodes
+            .iter()
+            .map(|node| match node.kind {
+                NodeKind::File(size) => size,
+                NodeKind::Dir(_) => 0,
+            })
+            .collect();
+        // children come after their parents
+        for id in (1..self.nodes.len()).rev() {
+            let parent = self.nodes[id].parent.unwrap();
+            sizes[parent] = overflow::add(sizes[parent], sizes[id], 7, "directory size")?;
+        }
+        Ok(sizes)
+    }
+
+    // follows `target` from `cwd` like a shell, creating missing directories;
+    // the path of a file in the way on failure
+    fn change_dir(&mut self, cwd: NodeId, target: &str) -> Result<NodeId, String> {
+        let start = if target.starts_with('/') { ROOT } else { cwd };
+        target
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(start, |dir, name| match name {
+                "." => Ok(dir),
+                ".." => Ok(self.nodes[dir].parent.unwrap_or(ROOT)),
+                _ => self
+                    .dir_entry(dir, name)
+                    .ok_or_else(|| self.child_path(dir, name)),
+            })
+    }
+
+    // the directory `name` in `dir`, created if needed; None if it's a file
+    fn dir_entry(&mut self, dir: NodeId, name: &str) -> Option<NodeId> {
+        let id = self.entry(dir, name, NodeKind::Dir(BTreeMap::new()));
+        self.is_dir(id).then_some(id)
+    }
+
+    // records `size` for the file `name` in `dir`; None if it's a directory
+    fn file_entry(&mut self, dir: NodeId, name: &str, size: u64) -> Option<NodeId> {
+        let id = self.entry(dir, name, NodeKind::File(size));
+        match &mut self.nodes[id].kind {
+            NodeKind::File(old) => {
+                *old = size;
+                Some(id)
+            }
+            NodeKind::Dir(_) => None,
+        }
+    }
+
+    fn entry(&mut self, dir: NodeId, name: &str, kind: NodeKind) -> NodeId {
+        let next = self.nodes.len();
+        let NodeKind::Dir(children) = &mut self.nodes[dir].kind else {
+            unreachable!("cwd is always a directory");
+        };
+        let id = *children.entry(name.to_string()).or_insert(next);
+        if id == next {
+            self.nodes.push(Node {
+                name: name.to_string(),
+                parent: Some(dir),
+                kind,
+            });
+        }
+        id
+    }
+
+    fn child_path(&self, dir: NodeId, name: &str) -> String {
+        match dir {
+            ROOT => format!("/{name}"),
+            _ => format!("{}/{name}", self.path(dir)),
+        }
+    }
+}
+
+/// Order in which `FileSystem::transcript` lists directories.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Traversal {
+    /// Like the puzzle input: into each subdirectory right after listing.
+    #[default]
+    DepthFirst,
+    /// A whole level before the next, lots of `cd ..`.
+    BreadthFirst,
+    /// Any order, so some directories are entered before they're listed.
+    Shuffled(u64),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TranscriptOptions {
+    traversal: Traversal,
+    revisits: usize,
+}
+
+impl TranscriptOptions {
+    pub fn new() -> Self {
+        TranscriptOptions::default()
+    }
+
+    pub fn with_traversal(mut self, traversal: Traversal) -> Self {
+        self.traversal = traversal;
+        self
+    }
+
+    /// Lists the first `revisits` directories a second time at the end.
+    pub fn with_revisits(mut self, revisits: usize) -> Self {
+        self.revisits = revisits;
+        self
+    }
+}
+
+impl FileSystem {
+    /// Snapshot of a real directory, and the paths left out of it because a
+    /// transcript can't hold their names (not UTF-8, or with whitespace).
+    /// Sizes are file lengths only, so `du -b` reports more: it also counts
+    /// every directory's own size, and a file with several hard links once
+    /// where this counts every link. Symlinks and special files are skipped.
+    pub fn from_dir(root: &Path) -> io::Result<(Self, Vec<PathBuf>)> {
+        let mut fs = FileSystem::new();
+        let mut skipped = Vec::new();
+        fs.read_dir(ROOT, root, &mut skipped)?;
+        Ok((fs, skipped))
+    }
+
+    fn read_dir(&mut self, dir: NodeId, path: &Path, skipped: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let name = match entry.file_name().into_string() {
+                Ok(name) if !name.contains(char::is_whitespace) => name,
+                _ => {
+                    skipped.push(entry.path());
+                    continue;
+                }
+            };
+
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                let child = self.dir_entry(dir, &name).expect("names are unique");
+                self.read_dir(child, &entry.path(), skipped)?;
+            } else if file_type.is_file() {
+                self.file_entry(dir, &name, entry.metadata()?.len());
+            }
+        }
+        Ok(())
+    }
+
+    /// Terminal output that `parse` turns back into this tree.
+    pub fn transcript(&self, options: &TranscriptOptions) -> String {
+        let mut order: Vec<NodeId> = match options.traversal {
+            Traversal::DepthFirst => self.directories_depth_first(),
+            Traversal::BreadthFirst => {
+                let mut order = Vec::new();
+                let mut queue = VecDeque::from([ROOT]);
+                while let Some(dir) = queue.pop_front() {
+                    order.push(dir);
+                    queue.extend(self.children(dir).filter(|&id| self.is_dir(id)));
+                }
+                order
             }
-            (size, _name, None) => {
-                let size: u32 = size.parse().unwrap();
-                sum += size;
+            Traversal::Shuffled(seed) => {
+                let mut order = self.directories_depth_first();
+                let mut state = seed | 1;
+                for i in (1..order.len()).rev() {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    order.swap(i, (state % (i as u64 + 1)) as usize);
+                }
+                order
+            }
+        };
+        let first_visits = order.len();
+        order.extend_from_within(..options.revisits.min(first_visits));
+
+        let mut lines = vec!["$ cd /".to_string()];
+        let mut cwd = ROOT;
+        for (visit, dir) in order.into_iter().enumerate() {
+            // revisits jump back to the root first, like a user starting over
+            if visit >= first_visits && cwd != ROOT {
+                lines.push("$ cd /".to_string());
+                cwd = ROOT;
+            }
+            self.navigate(&mut lines, cwd, dir);
+            cwd = dir;
+            lines.push("$ ls".to_string());
+            for child in self.children(dir) {
+                let node = &self.nodes[child];
+                match node.kind {
+                    NodeKind::Dir(_) => lines.push(format!("dir {}", node.name)),
+                    NodeKind::File(size) => lines.push(format!("{size} {}", node.name)),
+                }
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn directories_depth_first(&self) -> Vec<NodeId> {
+        self.walk()
+            .into_iter()
+            .map(|(id, _)| id)
+            .filter(|&id| self.is_dir(id))
+            .collect()
+    }
+
+    // `cd` one step at a time, up to the common ancestor and back down
+    fn navigate(&self, lines: &mut Vec<String>, from: NodeId, to: NodeId) {
+        let from_chain = self.ancestry(from);
+        let to_chain = self.ancestry(to);
+        let common = from_chain
+            .iter()
+            .zip(&to_chain)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        lines.extend((common..from_chain.len()).map(|_| "$ cd ..".to_string()));
+        for &dir in &to_chain[common..] {
+            lines.push(format!("$ cd {}", self.nodes[dir].name));
+        }
+    }
+
+    // the root down to `id`
+    fn ancestry(&self, id: NodeId) -> Vec<NodeId> {
+        let mut chain = vec![id];
+        while let Some(parent) = self.nodes[*chain.last().unwrap()].parent {
+            chain.push(parent);
+        }
+        chain.reverse();
+        chain
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EntryType {
+    Dir,
+    File,
+}
+
+/// Conditions for `DiskUsage::find`, all of which must hold. Names match a
+/// glob with `*` and `?`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Find {
+    name: Option<String>,
+    entry_type: Option<EntryType>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+}
+
+impl Find {
+    pub fn new() -> Self {
+        Find::default()
+    }
+
+    pub fn with_name(mut self, pattern: &str) -> Self {
+        self.name = Some(pattern.to_string());
+        self
+    }
+
+    pub fn with_type(mut self, entry_type: EntryType) -> Self {
+        self.entry_type = Some(entry_type);
+        self
+    }
+
+    pub fn with_min_size(mut self, size: u64) -> Self {
+        self.min_size = Some(size);
+        self
+    }
+
+    pub fn with_max_size(mut self, size: u64) -> Self {
+        self.max_size = Some(size);
+        self
+    }
+
+    fn matches(&self, name: &str, entry_type: EntryType, size: u64) -> bool {
+        self.name
+            .as_ref()
+            .is_none_or(|pattern| glob_match(pattern, name))
+            && self.entry_type.is_none_or(|t| t == entry_type)
+            && self.min_size.is_none_or(|min| size >= min)
+            && self.max_size.is_none_or(|max| size <= max)
+    }
+}
+
+/// A file system with the recursive size of every node.
+#[derive(Debug, Clone)]
+pub struct DiskUsage<'a> {
+    fs: &'a FileSystem,
+    sizes: Vec<u64>,
+}
+
+impl DiskUsage<'_> {
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.sizes[id]
+    }
+
+    /// Recursive size of the file or directory at `path`.
+    pub fn du(&self, path: &str) -> Option<u64> {
+        self.fs.lookup(path).map(|id| self.sizes[id])
+    }
+
+    /// Every directory with its size, subdirectories before their parent
+    /// like `du` prints them.
+    pub fn du_all(&self) -> Vec<(String, u64)> {
+        let mut walk = self.fs.walk();
+        walk.retain(|&(id, _)| self.fs.is_dir(id));
+        let mut result = Vec::with_capacity(walk.len());
+        // directories still open on the way down, emitted once we leave them
+        let mut pending: Vec<(NodeId, usize)> = Vec::new();
+        for (id, depth) in walk {
+            while pending.last().is_some_and(|&(_, d)| d >= depth) {
+                let (done, _) = pending.pop().unwrap();
+                result.push((self.fs.path(done), self.sizes[done]));
+            }
+            pending.push((id, depth));
+        }
+        while let Some((done, _)) = pending.pop() {
+            result.push((self.fs.path(done), self.sizes[done]));
+        }
+        result
+    }
+
+    /// Matching nodes in `walk` order.
+    pub fn find(&self, find: &Find) -> Vec<NodeId> {
+        self.fs
+            .walk()
+            .into_iter()
+            .map(|(id, _)| id)
+            .filter(|&id| {
+                let node = self.fs.node(id);
+                let entry_type = match node.kind {
+                    NodeKind::Dir(_) => EntryType::Dir,
+                    NodeKind::File(_) => EntryType::File,
+                };
+                find.matches(&node.name, entry_type, self.sizes[id])
+            })
+            .collect()
+    }
+
+    /// The `n` biggest matches, ties in `walk` order.
+    pub fn largest(&self, n: usize, find: &Find) -> Vec<NodeId> {
+        let mut found = self.find(find);
+        found.sort_by_key(|&id| Reverse(self.sizes[id]));
+        found.truncate(n);
+        found
+    }
+
+    /// The tree as in the puzzle, with directory sizes:
+    ///
+    /// ```text
+    /// - / (dir, size=48381165)
+    ///   - a (dir, size=94853)
+    ///     - e (dir, size=584)
+    ///       - i (file, size=584)
+    /// ```
+    pub fn render_tree(&self) -> String {
+        let mut result = String::new();
+        for (id, depth) in self.fs.walk() {
+            let node = self.fs.node(id);
+            let name = if id == ROOT { "/" } else { &node.name };
+            let entry_type = if self.fs.is_dir(id) { "dir" } else { "file" };
+            result += &format!(
+                "{:indent$}- {name} ({entry_type}, size={})\n",
+                "",
+                self.sizes[id],
+                indent = 2 * depth
+            );
+        }
+        result
+    }
+
+    /// `du`-style listing, sizes right-aligned.
+    pub fn render_du(&self) -> String {
+        let entries = self.du_all();
+        let width = entries
+            .iter()
+            .map(|(_, size)| size.to_string().len())
+            .max()
+            .unwrap_or(0);
+        entries
+            .iter()
+            .map(|(path, size)| format!("{size:>width$}  {path}\n"))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    Unsatisfiable { need: u64, used: u64 }, // even deleting everything is too little
+    LimitReached { expanded: usize },
+    Overflow(OverflowError),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::Unsatisfiable { need, used } => {
+                write!(f, "{need} bytes have to go but only {used} are used")
             }
-            _ => {
-                panic!("Unknown command: {}", line);
+            PlanError::LimitReached { expanded } => {
+                write!(f, "gave up after expanding {expanded} states")
             }
+            PlanError::Overflow(err) => write!(f, "{err}"),
         }
     }
+}
+
+impl std::error::Error for PlanError {}
+
+impl From<OverflowError> for PlanError {
+    fn from(err: OverflowError) -> Self {
+        PlanError::Overflow(err)
+    }
+}
+
+/// Directories to delete, none inside another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletionPlan {
+    pub need: u64,
+    pub deleted: u64,
+    pub paths: Vec<String>,
+}
 
-    if sum <= 100_000 {
-        *sum_atmost += sum;
+impl DiskUsage<'_> {
+    /// Bytes that have to go for `requirement` bytes to be free on a disk of
+    /// `capacity` bytes. An over-full disk first has to get back down to its
+    /// capacity.
+    pub fn space_to_free(&self, capacity: u64, requirement: u64) -> Result<u64, OverflowError> {
+        let used = self.sizes[ROOT];
+        if used > capacity {
+            overflow::add(requirement, used - capacity, 7, "space to free")
+        } else {
+            Ok(requirement.saturating_sub(capacity - used))
+        }
     }
 
-    if sum >= need_space && sum < *smallest {
-        *smallest = sum;
+    /// The puzzle's answer: the smallest single directory that frees enough.
+    pub fn plan_single(&self, capacity: u64, requirement: u64) -> Result<DeletionPlan, PlanError> {
+        let need = self.space_to_free(capacity, requirement)?;
+        if need == 0 {
+            return Ok(self.deletion_plan(need, &[]));
+        }
+        let dir = self
+            .fs
+            .directories()
+            .filter(|&dir| self.sizes[dir] >= need)
+            .min_by_key(|&dir| self.sizes[dir])
+            .ok_or(PlanError::Unsatisfiable {
+                need,
+                used: self.sizes[ROOT],
+            })?;
+        Ok(self.deletion_plan(need, &[dir]))
     }
 
-    sum
+    /// The fewest bytes deleted with any set of directories not nested in
+    /// each other. Branch and bound over the directories in `walk` order,
+    /// each either deleted whole or descended into, starting from the single
+    /// directory answer. Gives up after expanding `limit` states.
+    pub fn plan_deletions(
+        &self,
+        capacity: u64,
+        requirement: u64,
+        limit: usize,
+    ) -> Result<DeletionPlan, PlanError> {
+        let single = self.plan_single(capacity, requirement)?;
+        let need = single.need;
+        if need == 0 {
+            return Ok(single);
+        }
+
+        // (directory, size, position after its subtree) in walk order
+        let mut dirs: Vec<(NodeId, u64, usize)> = Vec::new();
+        let mut open: Vec<(usize, usize)> = Vec::new(); // (position, depth)
+        for (id, depth) in self.fs.walk() {
+            if !self.fs.is_dir(id) {
+                continue;
+            }
+            while open.last().is_some_and(|&(_, d)| d >= depth) {
+                let (position, _) = open.pop().unwrap();
+                dirs[position].2 = dirs.len();
+            }
+            open.push((dirs.len(), depth));
+            dirs.push((id, self.sizes[id], 0));
+        }
+        for (position, _) in open {
+            dirs[position].2 = dirs.len();
+        }
+
+        // the most that can still be deleted from each position on
+        let mut rest = vec![0; dirs.len() + 1];
+        for position in (0..dirs.len()).rev() {
+            let (_, size, next) = dirs[position];
+            rest[position] = size + rest[next];
+        }
+
+        let mut best = single.deleted;
+        let mut best_dirs: Vec<NodeId> = single
+            .paths
+            .iter()
+            .filter_map(|path| self.fs.lookup(path))
+            .collect();
+        let mut chosen: Vec<NodeId> = Vec::new();
+        // (position, bytes deleted, chosen so far, directory to add)
+        let mut stack = vec![(0, 0, 0, None)];
+        let mut expanded = 0;
+        while let Some((position, deleted, taken, add)) = stack.pop() {
+            if best == need {
+                break;
+            }
+            expanded += 1;
+            if expanded > limit {
+                return Err(PlanError::LimitReached { expanded: limit });
+            }
+
+            chosen.truncate(taken);
+            chosen.extend(add);
+            if deleted >= need {
+                if deleted < best {
+                    best = deleted;
+                    best_dirs.clone_from(&chosen);
+                }
+                continue;
+            }
+            if position == dirs.len() || deleted + rest[position] < need {
+                continue;
+            }
+
+            let (id, size, next) = dirs[position];
+            stack.push((position + 1, deleted, chosen.len(), None));
+            if size > 0 && deleted + size < best {
+                stack.push((next, deleted + size, chosen.len(), Some(id)));
+            }
+        }
+        Ok(self.deletion_plan(need, &best_dirs))
+    }
+
+    fn deletion_plan(&self, need: u64, dirs: &[NodeId]) -> DeletionPlan {
+        DeletionPlan {
+            need,
+            deleted: dirs.iter().map(|&dir| self.sizes[dir]).sum(),
+            paths: dirs.iter().map(|&dir| self.fs.path(dir)).collect(),
+        }
+    }
 }
 
-fn parse_line(line: &str) -> (&str, &str, Option<&str>) {
-    let mut iter = line.split_whitespace();
-    let first = iter.next().unwrap();
-    let second = iter.next().unwrap();
-    let third = iter.next();
-    (first, second, third)
+// `*` matches any run of characters, `?` any single one
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // where the last `*` was and how much of the name it has swallowed
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1_sample() {
-        let input = r#"$ cd /
+    const SAMPLE_INPUT: &str = r#"$ cd /
 $ ls
 dir a
 14848514 b.txt
@@ -108,7 +931,10 @@ $ ls
 8033020 d.log
 5626152 d.ext
 7214296 k"#;
-        let answer = solve_part1(input);
+
+    #[test]
+    fn test_part1_sample() {
+        let answer = solve_part1(SAMPLE_INPUT);
         assert_eq!(answer, 95437);
     }
 
@@ -146,12 +972,126 @@ $ cd .."#;
 
     #[test]
     fn test_part2_sample() {
+        let answer = solve_part2(SAMPLE_INPUT);
+        assert_eq!(answer, 24933642);
+    }
+    #[test]
+    fn test_part2() {
+        let input = include_str!("../input/day_07.txt");
+        let answer = solve_part2(input);
+        assert_eq!(answer, 8319096);
+    }
+
+    #[test]
+    fn test_params() {
+        let config =
+            Config::parse("day_07.need_space = 21700000\nday_07.small_dir_limit = 1000").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        assert_eq!(params.total_space, 70000000);
+
+        // only 81165 more bytes needed, so a (94853) is enough
+        let answer = solve_part2_with(SAMPLE_INPUT, &params);
+        assert_eq!(answer, 94853);
+        let answer = solve_part1_with(SAMPLE_INPUT, &params);
+        assert_eq!(answer, 584);
+    }
+
+    // two maximal files overflow the u64 directory sum
+    #[cfg(feature = "checked")]
+    fn overflow_input() -> String {
+        let mut input = String::from("$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\n");
+        input += &format!("{} big.bin\n", u64::MAX);
+        input += &format!("{} big2.bin\n", u64::MAX);
+        input
+    }
+
+    #[test]
+    fn test_try_solve() {
+        let input = include_str!("../input/day_07.txt");
+        let params = Params::default();
+        assert_eq!(try_solve_part1(input, &params), Ok(1743217));
+        assert_eq!(try_solve_part2(input, &params), Ok(8319096));
+
+        // a disk with more than 30000000 free needs nothing deleted
+        let input = "$ cd /\n$ ls\n1 a.txt";
+        assert_eq!(try_solve_part2(input, &params), Ok(0));
+        let params = Params {
+            total_space: 100,
+            need_space: 200,
+            ..Params::default()
+        };
+        assert_eq!(
+            try_solve_part2("$ cd /\n$ ls\n50 a.txt", &params),
+            Err(SolveError::Plan(PlanError::Unsatisfiable {
+                need: 150,
+                used: 50
+            }))
+        );
+
+        // answers past u32::MAX
+        let input = "$ cd /\n$ ls\ndir a\ndir b\n$ cd a\n$ ls\n3000000000 a.bin\n\
+                     $ cd /b\n$ ls\n3000000000 b.bin";
+        let large = Params {
+            small_dir_limit: u64::MAX,
+            total_space: 7_000_000_000,
+            need_space: 3_000_000_000,
+        };
+        assert_eq!(try_solve_part1(input, &large), Ok(12_000_000_000));
+        assert_eq!(try_solve_part2(input, &large), Ok(3_000_000_000));
+
+        let transcript = Err(SolveError::Transcript(TranscriptError::UnknownCommand {
+            line: 2,
+            text: "$ rm -rf a".to_string(),
+        }));
+        let input = "$ cd /\n$ rm -rf a";
+        assert_eq!(try_solve_part1(input, &params), transcript);
+        assert_eq!(try_solve_part2(input, &params), transcript);
+    }
+
+    #[test]
+    fn test_file_system() {
+        let fs = FileSystem::parse(SAMPLE_INPUT).unwrap();
+        assert_eq!(fs.len(), 14);
+        assert_eq!(fs.directories().count(), 4);
+
+        let e = fs.lookup("/a/e").unwrap();
+        assert_eq!(fs.path(e), "/a/e");
+        assert_eq!(fs.node(e).name, "e");
+        assert_eq!(fs.path(fs.node(e).parent.unwrap()), "/a");
+        assert_eq!(
+            fs.node(fs.lookup("/d/k").unwrap()).kind,
+            NodeKind::File(7214296)
+        );
+        assert_eq!(fs.lookup("/b.txt/x"), None);
+        assert_eq!(fs.path(ROOT), "/");
+
+        let names: Vec<_> = fs
+            .children(ROOT)
+            .map(|id| fs.node(id).name.as_str())
+            .collect();
+        assert_eq!(names, ["a", "b.txt", "c.dat", "d"]);
+
+        let sizes = fs.dir_sizes().unwrap();
+        assert_eq!(sizes[e], 584);
+        assert_eq!(sizes[fs.lookup("/a").unwrap()], 94853);
+        assert_eq!(sizes[fs.lookup("/d").unwrap()], 24933642);
+        assert_eq!(sizes[ROOT], 48381165);
+    }
+
+    #[test]
+    fn test_irregular_transcript() {
+        // jumps back to `/`, revisits and relists `a`, enters `d` without
+        // listing the root first and never climbs back out
         let input = r#"$ cd /
+$ cd a
+$ ls
+dir e
+29116 f
+$ cd /
 $ ls
 dir a
 14848514 b.txt
 8504156 c.dat
-dir d
 $ cd a
 $ ls
 dir e
@@ -163,19 +1103,393 @@ $ ls
 584 i
 $ cd ..
 $ cd ..
+$ cd ..
 $ cd d
 $ ls
 4060174 j
 8033020 d.log
 5626152 d.ext
 7214296 k"#;
-        let answer = solve_part2(input);
-        assert_eq!(answer, 24933642);
+        // ids depend on visit order, so compare by path
+        assert_eq!(
+            sized_paths(&FileSystem::parse(input).unwrap()),
+            sized_paths(&FileSystem::parse(SAMPLE_INPUT).unwrap())
+        );
+        assert_eq!(solve_part1(input), 95437);
+        assert_eq!(solve_part2(input), 24933642);
     }
+
     #[test]
-    fn test_part2() {
+    fn test_large_sizes() {
+        let input = "$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\n3000000000 a.bin\n3000000000 b.bin";
+        let fs = FileSystem::parse(input).unwrap();
+        let usage = fs.disk_usage().unwrap();
+        assert_eq!(usage.du("/"), Some(6_000_000_000));
+        assert_eq!(solve_part1(input), 0);
+    }
+
+    #[test]
+    fn test_du() {
+        let fs = FileSystem::parse(SAMPLE_INPUT).unwrap();
+        let usage = fs.disk_usage().unwrap();
+        assert_eq!(usage.du("/a"), Some(94853));
+        assert_eq!(usage.du("/d/k"), Some(7214296));
+        assert_eq!(usage.du("/x"), None);
+        assert_eq!(
+            usage.render_du(),
+            "     584  /a/e\n   94853  /a\n24933642  /d\n48381165  /\n"
+        );
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let fs = FileSystem::parse(SAMPLE_INPUT).unwrap();
+        let expected = r#"- / (dir, size=48381165)
+  - a (dir, size=94853)
+    - e (dir, size=584)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir, size=24933642)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+"#;
+        assert_eq!(fs.disk_usage().unwrap().render_tree(), expected);
+    }
+
+    #[test]
+    fn test_find() {
+        let fs = FileSystem::parse(SAMPLE_INPUT).unwrap();
+        let usage = fs.disk_usage().unwrap();
+        let paths =
+            |ids: Vec<NodeId>| -> Vec<String> { ids.into_iter().map(|id| fs.path(id)).collect() };
+
+        assert_eq!(
+            paths(usage.find(&Find::new().with_name("d*"))),
+            ["/d", "/d/d.ext", "/d/d.log"]
+        );
+        assert_eq!(
+            paths(usage.find(&Find::new().with_name("?.*"))),
+            ["/a/h.lst", "/b.txt", "/c.dat", "/d/d.ext", "/d/d.log"]
+        );
+        assert_eq!(
+            paths(usage.find(&Find::new().with_type(EntryType::Dir).with_max_size(100_000))),
+            ["/a", "/a/e"]
+        );
+        assert_eq!(
+            paths(
+                usage.find(
+                    &Find::new()
+                        .with_type(EntryType::File)
+                        .with_min_size(8_000_000)
+                )
+            ),
+            ["/b.txt", "/c.dat", "/d/d.log"]
+        );
+
+        let files = Find::new().with_type(EntryType::File);
+        assert_eq!(paths(usage.largest(2, &files)), ["/b.txt", "/c.dat"]);
+        assert_eq!(paths(usage.largest(2, &Find::new())), ["/", "/d"]);
+    }
+
+    #[test]
+    fn test_plan_deletions() {
+        let input = r#"$ cd /
+$ ls
+dir x
+dir y
+dir z
+$ cd x
+$ ls
+2 a
+dir w
+$ cd w
+$ ls
+38 b
+$ cd /y
+$ ls
+35 c
+$ cd /z
+$ ls
+100 d"#;
+        let fs = FileSystem::parse(input).unwrap();
+        let usage = fs.disk_usage().unwrap();
+        assert_eq!(usage.space_to_free(200, 100), Ok(75));
+
+        let single = usage.plan_single(200, 100).unwrap();
+        assert_eq!(single.paths, ["/z"]);
+        assert_eq!(single.deleted, 100);
+
+        // w and y only free 73, x and y exactly enough
+        let plan = usage.plan_deletions(200, 100, 1000).unwrap();
+        assert_eq!(plan.paths, ["/x", "/y"]);
+        assert_eq!((plan.need, plan.deleted), (75, 75));
+
+        let plan = usage.plan_deletions(200, 98, 1000).unwrap();
+        assert_eq!(plan.paths, ["/x/w", "/y"]);
+
+        assert!(usage
+            .plan_deletions(300, 100, 1000)
+            .unwrap()
+            .paths
+            .is_empty());
+        assert_eq!(
+            usage.plan_deletions(200, 400, 1000),
+            Err(PlanError::Unsatisfiable {
+                need: 375,
+                used: 175
+            })
+        );
+        assert_eq!(
+            usage.plan_deletions(200, 100, 2),
+            Err(PlanError::LimitReached { expanded: 2 })
+        );
+
+        // 175 used on a 100 byte disk: 75 over, plus the 50 required
+        assert_eq!(usage.space_to_free(100, 50), Ok(125));
+        assert_eq!(usage.plan_single(100, 50).unwrap().paths, ["/"]);
+        let plan = usage.plan_deletions(100, 50, 1000).unwrap();
+        assert_eq!(plan.paths, ["/y", "/z"]);
+        assert_eq!((plan.need, plan.deleted), (125, 135));
+        assert_eq!(
+            usage.plan_single(100, 150),
+            Err(PlanError::Unsatisfiable {
+                need: 225,
+                used: 175
+            })
+        );
+        #[cfg(feature = "checked")]
+        assert_eq!(
+            usage.plan_single(100, u64::MAX),
+            Err(PlanError::Overflow(OverflowError {
+                day: 7,
+                operation: "space to free"
+            }))
+        );
+    }
+
+    #[test]
+    fn test_plan_deletions_input() {
         let input = include_str!("../input/day_07.txt");
-        let answer = solve_part2(input);
-        assert_eq!(answer, 8319096);
+        let fs = FileSystem::parse(input).unwrap();
+        let usage = fs.disk_usage().unwrap();
+
+        let single = usage.plan_single(70000000, 30000000).unwrap();
+        assert_eq!(single.deleted, 8319096);
+        assert_eq!(single.paths.len(), 1);
+
+        let plan = usage
+            .plan_deletions(70000000, 30000000, 10_000_000)
+            .unwrap();
+        assert_eq!((plan.need, plan.deleted), (7442399, 7442399));
+        let sizes: u64 = plan.paths.iter().map(|path| usage.du(path).unwrap()).sum();
+        assert_eq!(sizes, plan.deleted);
+        for a in &plan.paths {
+            for b in &plan.paths {
+                assert!(a == b || !b.starts_with(&format!("{a}/")));
+            }
+        }
+    }
+
+    #[test]
+    fn test_glob() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*.txt", "b.txt"));
+        assert!(glob_match("a*b*c", "aXXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXXbYbZ"));
+        assert!(glob_match("?", "k"));
+        assert!(!glob_match("?", ""));
+        assert!(!glob_match("*.txt", "b.txt.bak"));
+    }
+
+    fn sized_paths(fs: &FileSystem) -> Vec<(String, u64)> {
+        let usage = fs.disk_usage().unwrap();
+        let mut paths: Vec<_> = (0..fs.len())
+            .map(|id| (fs.path(id), usage.size(id)))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_transcript() {
+        let fs = FileSystem::parse(SAMPLE_INPUT).unwrap();
+        let depth_first = fs.transcript(&TranscriptOptions::new());
+        assert!(depth_first.starts_with("$ cd /\n$ ls\ndir a\n14848514 b.txt\n"));
+        assert!(depth_first.contains("584 i\n$ cd ..\n$ cd ..\n$ cd d\n$ ls\n"));
+
+        let revisited = fs.transcript(&TranscriptOptions::new().with_revisits(2));
+        assert!(revisited.ends_with("7214296 k\n$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n$ cd a\n$ ls\ndir e\n29116 f\n2557 g\n62596 h.lst"));
+
+        let breadth_first =
+            fs.transcript(&TranscriptOptions::new().with_traversal(Traversal::BreadthFirst));
+        assert!(breadth_first.contains("$ cd ..\n$ cd d\n$ ls\n5626152 d.ext\n8033020 d.log\n4060174 j\n7214296 k\n$ cd ..\n$ cd a\n$ cd e"));
+
+        for options in [
+            TranscriptOptions::new(),
+            TranscriptOptions::new().with_traversal(Traversal::BreadthFirst),
+            TranscriptOptions::new().with_revisits(3),
+            TranscriptOptions::new().with_revisits(usize::MAX),
+            TranscriptOptions::new()
+                .with_traversal(Traversal::Shuffled(7))
+                .with_revisits(10),
+        ] {
+            let transcript = fs.transcript(&options);
+            let replayed = FileSystem::parse(&transcript).unwrap();
+            assert_eq!(sized_paths(&replayed), sized_paths(&fs), "{options:?}");
+            assert_eq!(solve_part1(&transcript), 95437);
+            assert_eq!(solve_part2(&transcript), 24933642);
+        }
+    }
+
+    #[test]
+    fn test_from_dir() {
+        let root = std::env::temp_dir().join(format!("aoc_day_07_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["a/e", "d", "empty"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let files: [(&str, usize); 6] = [
+            ("b.txt", 1200),
+            ("a/f", 300),
+            ("a/g.log", 45),
+            ("a/e/i", 584),
+            ("d/j", 4096),
+            ("d/k", 0),
+        ];
+        for (path, size) in files {
+            std::fs::write(root.join(path), vec![b'x'; size]).unwrap();
+        }
+
+        let (fs, skipped) = FileSystem::from_dir(&root).unwrap();
+        assert!(skipped.is_empty());
+        let usage = fs.disk_usage().unwrap();
+        assert_eq!(usage.du("/"), Some(6225));
+        assert_eq!(usage.du("/a"), Some(929));
+        assert_eq!(usage.du("/empty"), Some(0));
+        assert_eq!(fs.directories().count(), 5);
+
+        // sizes agree with the directory itself, whichever way it's walked
+        let actual = |path: &str| -> u64 {
+            files
+                .iter()
+                .filter(|(file, _)| file.starts_with(path))
+                .map(|&(_, size)| size as u64)
+                .sum()
+        };
+        for options in [
+            TranscriptOptions::new(),
+            TranscriptOptions::new()
+                .with_traversal(Traversal::Shuffled(42))
+                .with_revisits(2),
+        ] {
+            let replayed = FileSystem::parse(&fs.transcript(&options)).unwrap();
+            let usage = replayed.disk_usage().unwrap();
+            for path in ["a", "a/e", "d"] {
+                assert_eq!(usage.du(path), Some(actual(path)));
+            }
+            assert_eq!(usage.du("/"), Some(actual("")));
+        }
+
+        // GNU `du -b`, where there is one, minus the directories' own sizes
+        fn dir_sizes(path: &Path) -> u64 {
+            let own = std::fs::metadata(path).unwrap().len();
+            std::fs::read_dir(path)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.is_dir())
+                .map(|path| dir_sizes(&path))
+                .sum::<u64>()
+                + own
+        }
+        let du = |path: &str| -> Option<u64> {
+            let path = root.join(path);
+            let output = std::process::Command::new("du")
+                .arg("-bs")
+                .arg(&path)
+                .output()
+                .ok()?;
+            let text = String::from_utf8(output.stdout).ok()?;
+            let total: u64 = text.split_whitespace().next()?.parse().ok()?;
+            output.status.success().then(|| total - dir_sizes(&path))
+        };
+        for path in ["", "a", "a/e", "d", "empty"] {
+            if let Some(size) = du(path) {
+                assert_eq!(usage.du(&format!("/{path}")), Some(size), "{path}");
+            }
+        }
+
+        std::fs::write(root.join("d/with space"), "").unwrap();
+        std::fs::create_dir(root.join("a/tab\tdir")).unwrap();
+        std::fs::write(root.join("a/tab\tdir/l"), "xyz").unwrap();
+        let (fs, mut skipped) = FileSystem::from_dir(&root).unwrap();
+        skipped.sort();
+        assert_eq!(
+            skipped,
+            [root.join("a/tab\tdir"), root.join("d/with space")]
+        );
+        assert_eq!(fs.disk_usage().unwrap().du("/"), Some(6225));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_transcript_errors() {
+        assert_eq!(
+            FileSystem::parse("$ cd /\n$ rm -rf a"),
+            Err(TranscriptError::UnknownCommand {
+                line: 2,
+                text: "$ rm -rf a".to_string()
+            })
+        );
+        assert_eq!(
+            FileSystem::parse("$ cd /\n12 a.txt"),
+            Err(TranscriptError::UnexpectedOutput {
+                line: 2,
+                text: "12 a.txt".to_string()
+            })
+        );
+        assert_eq!(
+            FileSystem::parse("$ ls\nbig a.txt"),
+            Err(TranscriptError::MalformedEntry {
+                line: 2,
+                text: "big a.txt".to_string()
+            })
+        );
+        assert_eq!(
+            FileSystem::parse("$ ls\n12 a\n$ cd /b/../a/c"),
+            Err(TranscriptError::Conflict {
+                line: 3,
+                path: "/a".to_string()
+            })
+        );
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let overflow = |result: Result<u64, SolveError>| match result {
+            Err(SolveError::Overflow(err)) => err,
+            other => panic!("expected an overflow, got {other:?}"),
+        };
+        let err = overflow(try_solve_part1(&overflow_input(), &Params::default()));
+        assert_eq!(err.day, 7);
+        assert_eq!(err.operation, "directory size");
+        let err = overflow(try_solve_part2(&overflow_input(), &Params::default()));
+        assert_eq!(err.operation, "directory size");
+
+        // `/` and `/a` both fit, their sum doesn't
+        let input = format!("$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\n{} a.bin", 1_u64 << 63);
+        let params = Params {
+            small_dir_limit: u64::MAX,
+            ..Params::default()
+        };
+        let err = overflow(try_solve_part1(&input, &params));
+        assert_eq!(err.operation, "sum of small directories");
     }
 }
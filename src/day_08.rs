@@ -45,7 +45,7 @@ fn load_grid(input: &str) -> Vec<Vec<u8>> {
     grid
 }
 
-fn is_visible(grid: &Vec<Vec<u8>>, x: usize, y: usize) -> bool {
+fn is_visible(grid: &[Vec<u8>], x: usize, y: usize) -> bool {
     if x == 0 || y == 0 || x == grid[0].len() - 1 || y == grid.len() - 1 {
         return true;
     }
@@ -54,8 +54,8 @@ fn is_visible(grid: &Vec<Vec<u8>>, x: usize, y: usize) -> bool {
 
     // check left
     let mut visible = true;
-    for i in 0..x {
-        if grid[y][i] >= cur {
+    for &h in grid[y].iter().take(x) {
+        if h >= cur {
             visible = false;
             break;
         }
@@ -66,8 +66,8 @@ fn is_visible(grid: &Vec<Vec<u8>>, x: usize, y: usize) -> bool {
 
     // check right
     let mut visible = true;
-    for i in x + 1..grid[0].len() {
-        if grid[y][i] >= cur {
+    for &h in grid[y].iter().skip(x + 1) {
+        if h >= cur {
             visible = false;
             break;
         }
@@ -121,10 +121,9 @@ fn get_score(grid: &[Vec<u8>], x: usize, y: usize) -> u32 {
 
     // check right
     let mut right_score = 0;
-    for i in x + 1..grid[0].len() {
+    for &h in grid[y].iter().skip(x + 1) {
         right_score += 1;
 
-        let h = grid[y][i];
         if h >= cur {
             break;
         }
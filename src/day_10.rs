@@ -1,16 +1,76 @@
 use std::str::FromStr;
 
+use crate::config::{Config, ConfigError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub sample_cycles: Vec<i32>, // cycles whose signal strength is summed in part 1
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            sample_cycles: vec![20, 60, 100, 140, 180, 220],
+        }
+    }
+}
+
+impl Params {
+    /// Every config key `from_config` reads.
+    pub const KEYS: &'static [&'static str] = &["day_10.sample_cycles"];
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let default = Params::default();
+        Ok(Params {
+            sample_cycles: config.get_valid_list_or(
+                "day_10.sample_cycles",
+                default.sample_cycles,
+                |&cycle| cycle > 0,
+            )?,
+        })
+    }
+}
+
 pub fn solve_part1(input: &str) -> i32 {
+    solve_part1_with(input, &Params::default())
+}
+
+pub fn solve_part1_with(input: &str, params: &Params) -> i32 {
+    try_solve_part1(input, params).unwrap()
+}
+
+/// Fails when a sample cycle comes after the program has finished.
+pub fn try_solve_part1(input: &str, params: &Params) -> Result<i32, ConfigError> {
     let instructions = parse_instructoins(input);
+    let program_cycles: u32 = instructions.iter().map(Instruction::cycle).sum();
+    if params
+        .sample_cycles
+        .iter()
+        .any(|&cycle| i64::from(cycle) > i64::from(program_cycles))
+    {
+        let cycles: Vec<String> = params.sample_cycles.iter().map(i32::to_string).collect();
+        return Err(ConfigError::InvalidValue(
+            "day_10.sample_cycles".to_string(),
+            cycles.join(", "),
+        ));
+    }
     let mut ops = instructions.into_iter();
     let mut x = 1;
     let mut cur_instruction: Option<(Instruction, u32)> = None;
     let mut sum = 0;
 
-    for cycle in 1..=220 {
-        run_instruction(&mut cur_instruction, &mut x, &mut ops, cycle, &mut sum);
+    let last_cycle = params.sample_cycles.iter().copied().max().unwrap_or(0);
+    for cycle in 1..=last_cycle {
+        run_instruction(
+            &mut cur_instruction,
+            &mut x,
+            &mut ops,
+            cycle,
+            &params.sample_cycles,
+            &mut sum,
+        );
     }
-    sum
+    Ok(sum)
 }
 
 pub fn solve_part2(input: &str) -> String {
@@ -38,7 +98,7 @@ pub fn solve_part2(input: &str) -> String {
             result += ".";
         }
 
-        run_cur_instruction(&mut cur_instruction, &mut x, &mut ops, cycle, &mut sum);
+        run_cur_instruction(&mut cur_instruction, &mut x, &mut ops, cycle, &[], &mut sum);
     }
 
     println!("{result}");
@@ -63,13 +123,14 @@ fn run_cur_instruction(
     x: &mut i32,
     ops: &mut std::vec::IntoIter<Instruction>,
     cycle: i32,
+    sample_cycles: &[i32],
     sum: &mut i32,
 ) {
     if cur_instruction.is_none() {
         let op = ops.next().unwrap();
         *cur_instruction = Some((op, op.cycle()));
     }
-    if cycle == 20 || (cycle + 20) % 40 == 0 {
+    if sample_cycles.contains(&cycle) {
         *sum += *x * cycle;
     }
 }
@@ -79,10 +140,11 @@ fn run_instruction(
     x: &mut i32,
     ops: &mut std::vec::IntoIter<Instruction>,
     cycle: i32,
+    sample_cycles: &[i32],
     sum: &mut i32,
 ) {
     apply_prev_instruction(cur_instruction, x);
-    run_cur_instruction(cur_instruction, x, ops, cycle, sum);
+    run_cur_instruction(cur_instruction, x, ops, cycle, sample_cycles, sum);
     // if let Some((op, mut count)) = *cur_instruction {
     //     count -= 1;
     //     if count == 0 {
@@ -306,6 +368,36 @@ noop"#;
         assert_eq!(answer, 11720);
     }
 
+    #[test]
+    fn test_part1_params() {
+        let input = include_str!("../input/day_10.txt");
+
+        // summing one sample point at a time adds up to the default answer
+        let mut sum = 0;
+        for cycle in Params::default().sample_cycles {
+            let config = Config::parse(&format!("day_10.sample_cycles = {cycle}")).unwrap();
+            let params = Params::from_config(&config).unwrap();
+            sum += solve_part1_with(input, &params);
+        }
+        assert_eq!(sum, 11720);
+
+        // the program runs for 240 cycles
+        let config = Config::parse("day_10.sample_cycles = 240").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        assert!(try_solve_part1(input, &params).is_ok());
+        let config = Config::parse("day_10.sample_cycles = 20, 5000").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        assert_eq!(
+            try_solve_part1(input, &params),
+            Err(ConfigError::InvalidValue(
+                "day_10.sample_cycles".to_string(),
+                "20, 5000".to_string()
+            ))
+        );
+        let config = Config::parse("day_10.sample_cycles = 0").unwrap();
+        assert!(Params::from_config(&config).is_err());
+    }
+
     #[test]
     fn test_part2_sample() {
         let input = r#"addx 15
@@ -1,11 +1,67 @@
+use crate::{
+    config::{Config, ConfigError},
+    overflow::{self, OverflowError},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub part1_rounds: u32,
+    pub part2_rounds: u32,
+    pub relief: u64, // part 1 worry level is divided by this after each inspection
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            part1_rounds: 20,
+            part2_rounds: 10000,
+            relief: 3,
+        }
+    }
+}
+
+impl Params {
+    /// Every config key `from_config` reads.
+    pub const KEYS: &'static [&'static str] = &[
+        "day_11.part1_rounds",
+        "day_11.part2_rounds",
+        "day_11.relief",
+    ];
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let default = Params::default();
+        Ok(Params {
+            part1_rounds: config.get_or("day_11.part1_rounds", default.part1_rounds)?,
+            part2_rounds: config.get_or("day_11.part2_rounds", default.part2_rounds)?,
+            relief: config.get_valid_or("day_11.relief", default.relief, |&relief| relief > 0)?,
+        })
+    }
+}
+
 pub fn solve_part1(input: &str) -> u64 {
+    solve_part1_with(input, &Params::default())
+}
+
+pub fn solve_part2(input: &str) -> u64 {
+    solve_part2_with(input, &Params::default())
+}
+
+pub fn solve_part1_with(input: &str, params: &Params) -> u64 {
+    try_solve_part1(input, params).unwrap()
+}
+
+pub fn solve_part2_with(input: &str, params: &Params) -> u64 {
+    try_solve_part2(input, params).unwrap()
+}
+
+pub fn try_solve_part1(input: &str, params: &Params) -> Result<u64, OverflowError> {
     let mut monkeys = load_monkeys(input);
 
-    for _round in 1..=20 {
+    for _round in 1..=params.part1_rounds {
         for monkey_idx in 0..monkeys.len() {
             monkeys[monkey_idx].inspected += monkeys[monkey_idx].items.len() as u64;
             for item in monkeys[monkey_idx].items.clone() {
-                let changed = monkeys[monkey_idx].operation.run(item) / 3;
+                let changed = monkeys[monkey_idx].operation.run(item)? / params.relief;
                 let test_result = monkeys[monkey_idx].test.run(changed);
                 let throw_to = if test_result {
                     monkeys[monkey_idx].if_true_throw_to as usize
@@ -19,29 +75,28 @@ pub fn solve_part1(input: &str) -> u64 {
         }
     }
 
-    monkeys.sort_by(|a, b| b.inspected.cmp(&a.inspected));
-    monkeys[0].inspected * monkeys[1].inspected
+    monkey_business(monkeys)
 }
 
-pub fn solve_part2(input: &str) -> u64 {
+pub fn try_solve_part2(input: &str, params: &Params) -> Result<u64, OverflowError> {
     let mut monkeys = load_monkeys(input);
 
     let mut total_divider = 1;
     for monkey in &monkeys {
         match &monkey.test {
             Test::DivisibleBy(divider) => {
-                total_divider *= divider;
+                total_divider = overflow::mul(total_divider, *divider, 11, "product of divisors")?;
             }
         }
     }
     // println!("total_divider: {}", total_divider);
 
     // for _round in 1..=10000 {
-    for _round in 1..=10000 {
+    for _round in 1..=params.part2_rounds {
         for monkey_idx in 0..monkeys.len() {
             monkeys[monkey_idx].inspected += monkeys[monkey_idx].items.len() as u64;
             for item in monkeys[monkey_idx].items.clone() {
-                let changed = monkeys[monkey_idx].operation.run(item) % total_divider;
+                let changed = monkeys[monkey_idx].operation.run(item)? % total_divider;
                 let test_result = monkeys[monkey_idx].test.run(changed);
                 let throw_to = if test_result {
                     monkeys[monkey_idx].if_true_throw_to as usize
@@ -60,8 +115,17 @@ pub fn solve_part2(input: &str) -> u64 {
         // }
     }
 
-    monkeys.sort_by(|a, b| b.inspected.cmp(&a.inspected));
-    monkeys[0].inspected * monkeys[1].inspected
+    monkey_business(monkeys)
+}
+
+fn monkey_business(mut monkeys: Vec<Monkey>) -> Result<u64, OverflowError> {
+    monkeys.sort_by_key(|monkey| std::cmp::Reverse(monkey.inspected));
+    overflow::mul(
+        monkeys[0].inspected,
+        monkeys[1].inspected,
+        11,
+        "monkey business",
+    )
 }
 
 fn load_monkeys(input: &str) -> Vec<Monkey> {
@@ -171,12 +235,12 @@ enum Operation {
 }
 
 impl Operation {
-    fn run(&self, item: u64) -> u64 {
+    fn run(&self, item: u64) -> Result<u64, OverflowError> {
         match self {
-            Operation::Add(n) => item + n,
-            Operation::AddOld => item + item,
-            Operation::Multiply(n) => item * n,
-            Operation::MultiplyOld => item * item,
+            Operation::Add(n) => overflow::add(item, *n, 11, "old + n"),
+            Operation::AddOld => overflow::add(item, item, 11, "old + old"),
+            Operation::Multiply(n) => overflow::mul(item, *n, 11, "old * n"),
+            Operation::MultiplyOld => overflow::mul(item, item, 11, "old * old"),
         }
     }
 }
@@ -189,7 +253,7 @@ enum Test {
 impl Test {
     fn run(&self, item: u64) -> bool {
         match self {
-            Test::DivisibleBy(n) => item % n == 0,
+            Test::DivisibleBy(n) => item.is_multiple_of(*n),
         }
     }
 }
@@ -268,4 +332,58 @@ mod tests {
         let answer = solve_part2(input);
         assert_eq!(answer, 21115867968);
     }
+
+    // part 1 has no modulo, so squaring a worry level of 2^32 wraps u64
+    #[cfg(feature = "checked")]
+    fn overflow_input() -> String {
+        format!(
+            r#"Monkey 0:
+  Starting items: {}
+  Operation: new = old * old
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 1
+  Operation: new = old + 1
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 0"#,
+            1_u64 << 32
+        )
+    }
+
+    #[test]
+    fn test_params() {
+        // the puzzle's part 2 walkthrough lists the monkey counts after round 20
+        let config = Config::parse("day_11.part2_rounds = 20").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        assert_eq!(solve_part2_with(SAMPLE_INPUT, &params), 103 * 99);
+
+        // part 1 without relief is part 2 without the modulo
+        let config = Config::parse("day_11.part1_rounds = 1\nday_11.relief = 1").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        assert_eq!(solve_part1_with(SAMPLE_INPUT, &params), 4 * 6);
+
+        let config = Config::parse("day_11.relief = 0").unwrap();
+        assert!(Params::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_operation_run() {
+        assert_eq!(Operation::Add(3).run(4), Ok(7));
+        assert_eq!(Operation::MultiplyOld.run(4), Ok(16));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let err = Operation::MultiplyOld.run(1 << 32).unwrap_err();
+        assert_eq!(err.day, 11);
+        assert_eq!(err.operation, "old * old");
+
+        let err = try_solve_part1(&overflow_input(), &Params::default()).unwrap_err();
+        assert_eq!(err.operation, "old * old");
+    }
 }
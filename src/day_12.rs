@@ -37,7 +37,7 @@ fn next_node(visited: &[bool], distance: &[u32]) -> Option<usize> {
     min_idx
 }
 
-fn shortest_path(start: usize, end: usize, map: &mut Vec<Vec<u32>>) -> u32 {
+fn shortest_path(start: usize, end: usize, map: &mut [Vec<u32>]) -> u32 {
     let mut visited = vec![false; map.len()];
     let mut distance = map[start].clone();
 
@@ -133,12 +133,12 @@ fn load_map(input: &str) -> (usize, Vec<usize>, usize, Vec<Vec<u32>>) {
     let end = end.0 + end.1 * data[0].len();
 
     let mut map = vec![vec![INF; data.len() * data[0].len()]; data.len() * data[0].len()];
-    for src in 0..map.len() {
-        for dst in 0..map.len() {
+    for (src, row) in map.iter_mut().enumerate() {
+        for (dst, cost) in row.iter_mut().enumerate() {
             if src == dst {
-                map[src][dst] = 0;
+                *cost = 0;
             } else if can_go(src, dst, &data) {
-                map[src][dst] = 1;
+                *cost = 1;
             }
         }
     }
@@ -167,7 +167,7 @@ abdefghi"#;
     #[test]
     fn test_load_data() {
         let data = load_data(SAMPLE_INPUT);
-        assert_eq!(can_go(20, 21, &data), true);
+        assert!(can_go(20, 21, &data));
     }
 
     #[test]
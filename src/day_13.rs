@@ -94,18 +94,16 @@ impl Ord for List {
     }
 }
 
-impl ToString for List {
-    fn to_string(&self) -> String {
-        let mut result = String::new();
-        result.push('[');
+impl std::fmt::Display for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
         for (i, value) in self.values.iter().enumerate() {
             if i > 0 {
-                result.push_str(", ");
+                write!(f, ", ")?;
             }
-            result.push_str(&value.to_string());
+            write!(f, "{value}")?;
         }
-        result.push(']');
-        result
+        write!(f, "]")
     }
 }
 
@@ -115,11 +113,11 @@ enum Value {
     Value(i32),
 }
 
-impl ToString for Value {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Value::List(list) => list.to_string(),
-            Value::Value(value) => value.to_string(),
+            Value::List(list) => write!(f, "{list}"),
+            Value::Value(value) => write!(f, "{value}"),
         }
     }
 }
@@ -5,11 +5,7 @@ pub fn solve_part1(input: &str) -> i32 {
     let mut endless = None;
     let mut prev = None;
     'outer: for idx in 1.. {
-        let mut p = if let Some(prev) = prev.take() {
-            prev
-        } else {
-            (500, 0)
-        };
+        let mut p = prev.take().unwrap_or((500, 0));
 
         loop {
             if p.1 >= max_y {
@@ -67,11 +63,7 @@ pub fn solve_part2(input: &str) -> i32 {
     let mut prev = None;
     let mut full = None;
     'outer: for idx in 1.. {
-        let mut p = if let Some(prev) = prev.take() {
-            prev
-        } else {
-            (500, 0)
-        };
+        let mut p = prev.take().unwrap_or((500, 0));
 
         loop {
             // go down
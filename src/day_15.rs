@@ -1,5 +1,43 @@
 use std::collections::HashSet;
 
+use crate::{
+    config::{Config, ConfigError},
+    overflow::{self, OverflowError},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub row: i32,               // part 1 row to scan
+    pub size: i32,              // part 2 search area is 0..=size on both axes
+    pub tuning_multiplier: i64, // tuning frequency = x * tuning_multiplier + y
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            row: 2000000,
+            size: 4000000,
+            tuning_multiplier: 4000000,
+        }
+    }
+}
+
+impl Params {
+    /// Every config key `from_config` reads.
+    pub const KEYS: &'static [&'static str] =
+        &["day_15.row", "day_15.size", "day_15.tuning_multiplier"];
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let default = Params::default();
+        Ok(Params {
+            row: config.get_or("day_15.row", default.row)?,
+            size: config.get_or("day_15.size", default.size)?,
+            tuning_multiplier: config
+                .get_or("day_15.tuning_multiplier", default.tuning_multiplier)?,
+        })
+    }
+}
+
 pub fn parse_input(input: &str) -> Vec<(i32, i32, i32, i32)> {
     let mut parsed = Vec::new();
     for line in input.lines() {
@@ -26,57 +64,92 @@ pub fn parse_input(input: &str) -> Vec<(i32, i32, i32, i32)> {
 }
 
 pub fn solve_part1(input: &str, row: i32) -> i32 {
-    let parsed = parse_input(input);
-    impossible_beacon_count_at_row(&parsed, row)
+    let params = Params {
+        row,
+        ..Params::default()
+    };
+    solve_part1_with(input, &params)
 }
 
 pub fn solve_part2(input: &str, size: i32) -> i64 {
+    let params = Params {
+        size,
+        ..Params::default()
+    };
+    solve_part2_with(input, &params)
+}
+
+pub fn solve_part1_with(input: &str, params: &Params) -> i32 {
+    try_solve_part1(input, params).unwrap()
+}
+
+pub fn solve_part2_with(input: &str, params: &Params) -> i64 {
+    try_solve_part2(input, params).unwrap()
+}
+
+pub fn try_solve_part1(input: &str, params: &Params) -> Result<i32, OverflowError> {
     let parsed = parse_input(input);
-    find_empty_pos(&parsed, size)
+    impossible_beacon_count_at_row(&parsed, params.row)
 }
 
-fn find_empty_pos(input: &[(i32, i32, i32, i32)], size: i32) -> i64 {
+pub fn try_solve_part2(input: &str, params: &Params) -> Result<i64, OverflowError> {
+    let parsed = parse_input(input);
+    find_empty_pos(&parsed, params.size, params.tuning_multiplier)
+}
+
+fn find_empty_pos(
+    input: &[(i32, i32, i32, i32)],
+    size: i32,
+    tuning_multiplier: i64,
+) -> Result<i64, OverflowError> {
     for &(sx, sy, bx, by) in input {
-        let points = get_cover_points(sx, sy, bx, by);
+        let points = get_cover_points(sx, sy, bx, by)?;
         for (x, y) in points {
             if x < 0 || x > size || y < 0 || y > size {
                 continue;
             }
 
-            if is_empty_at_pos(input, x, y) {
-                return (x as i64) * 4000000 + (y as i64);
+            if is_empty_at_pos(input, x, y)? {
+                let frequency =
+                    overflow::mul(x as i64, tuning_multiplier, 15, "x * tuning multiplier")?;
+                return overflow::add(frequency, y as i64, 15, "tuning frequency");
             }
         }
     }
-    0
+    Ok(0)
 }
 
-fn is_empty_at_pos(input: &[(i32, i32, i32, i32)], x: i32, y: i32) -> bool {
+fn is_empty_at_pos(input: &[(i32, i32, i32, i32)], x: i32, y: i32) -> Result<bool, OverflowError> {
     for &(sx, sy, bx, by) in input {
-        if is_in_area(sx, sy, bx, by, x, y) {
-            return false;
+        if is_in_area(sx, sy, bx, by, x, y)? {
+            return Ok(false);
         }
     }
 
-    true
+    Ok(true)
+}
+
+fn distance(ax: i32, ay: i32, bx: i32, by: i32) -> Result<i32, OverflowError> {
+    let dist = overflow::add(ax.abs_diff(bx), ay.abs_diff(by), 15, "manhattan distance")?;
+    overflow::cast(dist, 15, "manhattan distance")
 }
 
-fn is_in_area(sx: i32, sy: i32, bx: i32, by: i32, px: i32, py: i32) -> bool {
-    let len = (bx - sx).abs() + (by - sy).abs();
-    let dist = (px - sx).abs() + (py - sy).abs();
+fn is_in_area(sx: i32, sy: i32, bx: i32, by: i32, px: i32, py: i32) -> Result<bool, OverflowError> {
+    let len = distance(sx, sy, bx, by)?;
+    let dist = distance(sx, sy, px, py)?;
 
-    dist <= len
+    Ok(dist <= len)
 }
 
-fn get_cover_points(sx: i32, sy: i32, bx: i32, by: i32) -> Vec<(i32, i32)> {
+fn get_cover_points(sx: i32, sy: i32, bx: i32, by: i32) -> Result<Vec<(i32, i32)>, OverflowError> {
     let mut points = Vec::new();
 
-    let len = (bx - sx).abs() + (by - sy).abs() + 1;
+    let len = overflow::add(distance(sx, sy, bx, by)?, 1, 15, "sensor range + 1")?;
 
     for i in -len..=len {
-        let x = sx + i;
-        let y1 = sy + (len - i.abs());
-        let y2 = sy - (len - i.abs());
+        let x = overflow::add(sx, i, 15, "cover point x")?;
+        let y1 = overflow::add(sy, len - i.abs(), 15, "cover point y")?;
+        let y2 = overflow::sub(sy, len - i.abs(), 15, "cover point y")?;
         if y1 == y2 {
             points.push((x, y1));
         } else {
@@ -85,10 +158,13 @@ fn get_cover_points(sx: i32, sy: i32, bx: i32, by: i32) -> Vec<(i32, i32)> {
         }
     }
 
-    points
+    Ok(points)
 }
 
-fn impossible_beacon_count_at_row(input: &[(i32, i32, i32, i32)], row: i32) -> i32 {
+fn impossible_beacon_count_at_row(
+    input: &[(i32, i32, i32, i32)],
+    row: i32,
+) -> Result<i32, OverflowError> {
     let mut impossibles = HashSet::<i32>::new();
 
     for (sx, sy, bx, by) in input {
@@ -103,16 +179,17 @@ fn impossible_beacon_count_at_row(input: &[(i32, i32, i32, i32)], row: i32) -> i
 
     let prev_count = impossibles.len();
 
-    for (sx, sy, bx, by) in input {
-        let len = (bx - sx).abs() + (by - sy).abs();
+    for &(sx, sy, bx, by) in input {
+        let len = distance(sx, sy, bx, by)?;
+        let row_dist = distance(sx, sy, sx, row)?;
 
-        if (*sy - row).abs() > len {
+        if row_dist > len {
             continue;
         }
 
-        let count = len - (*sy - row).abs();
+        let count = len - row_dist;
         for i in -count..=count {
-            impossibles.insert(*sx + i);
+            impossibles.insert(overflow::add(sx, i, 15, "covered x")?);
         }
     }
 
@@ -123,9 +200,9 @@ fn impossible_beacon_count_at_row(input: &[(i32, i32, i32, i32)], row: i32) -> i
             print!(".");
         }
     }
-    println!("");
+    println!();
 
-    (impossibles.len() - prev_count) as i32
+    Ok((impossibles.len() - prev_count) as i32)
 }
 
 #[cfg(test)]
@@ -187,17 +264,17 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3 "#;
     #[test]
     fn test_is_in_area() {
         let (sx, sy, bx, by) = (10, 10, 20, 10);
-        assert_eq!(is_in_area(sx, sy, bx, by, 10, 10), true);
-        assert_eq!(is_in_area(sx, sy, bx, by, 20, 10), true);
-        assert_eq!(is_in_area(sx, sy, bx, by, 21, 10), false);
-        assert_eq!(is_in_area(sx, sy, bx, by, 0, 10), true);
-        assert_eq!(is_in_area(sx, sy, bx, by, -1, 10), false);
+        assert!(is_in_area(sx, sy, bx, by, 10, 10).unwrap());
+        assert!(is_in_area(sx, sy, bx, by, 20, 10).unwrap());
+        assert!(!is_in_area(sx, sy, bx, by, 21, 10).unwrap());
+        assert!(is_in_area(sx, sy, bx, by, 0, 10).unwrap());
+        assert!(!is_in_area(sx, sy, bx, by, -1, 10).unwrap());
     }
 
     #[test]
     fn test_cover_points() {
         let (sx, sy, bx, by) = (10, 10, 12, 10);
-        let cover_points = get_cover_points(sx, sy, bx, by);
+        let cover_points = get_cover_points(sx, sy, bx, by).unwrap();
 
         assert_eq!(cover_points.len(), 12);
         assert_eq!(
@@ -218,4 +295,37 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3 "#;
             ]
         )
     }
+
+    // a sensor near i32::MAX whose beacon sits near i32::MIN
+    #[cfg(feature = "checked")]
+    fn overflow_input() -> String {
+        format!(
+            "Sensor at x={}, y=0: closest beacon is at x={}, y=0",
+            i32::MAX - 1,
+            i32::MIN + 1
+        )
+    }
+
+    #[test]
+    fn test_try_solve_sample() {
+        let config = Config::parse("day_15.row = 10\nday_15.size = 20").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        assert_eq!(try_solve_part1(SAMPLE_INPUT, &params), Ok(26));
+        assert_eq!(try_solve_part2(SAMPLE_INPUT, &params), Ok(56000011));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let params = Params {
+            row: 0,
+            ..Params::default()
+        };
+        let err = try_solve_part1(&overflow_input(), &params).unwrap_err();
+        assert_eq!(err.day, 15);
+        assert_eq!(err.operation, "manhattan distance");
+
+        let err = get_cover_points(0, i32::MAX - 1, 0, i32::MAX).unwrap_err();
+        assert_eq!(err.operation, "cover point y");
+    }
 }
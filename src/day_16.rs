@@ -2,18 +2,91 @@
 
 use std::collections::{BTreeMap, HashMap};
 
+use crate::config::{Config, ConfigError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub minutes: i32,
+    pub minutes_with_elephant: i32,
+    pub start: String,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            minutes: 30,
+            minutes_with_elephant: 26,
+            start: "AA".to_string(),
+        }
+    }
+}
+
+impl Params {
+    /// Every config key `from_config` reads.
+    pub const KEYS: &'static [&'static str] = &[
+        "day_16.minutes",
+        "day_16.minutes_with_elephant",
+        "day_16.start",
+    ];
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let default = Params::default();
+        Ok(Params {
+            minutes: config.get_valid_or("day_16.minutes", default.minutes, |&m| m >= 0)?,
+            minutes_with_elephant: config.get_valid_or(
+                "day_16.minutes_with_elephant",
+                default.minutes_with_elephant,
+                |&m| m >= 0,
+            )?,
+            start: config.get_valid_or("day_16.start", default.start, |start: &String| {
+                start.len() == 2 && start.chars().all(|c| c.is_ascii_uppercase())
+            })?,
+        })
+    }
+}
+
 pub fn solve_part1(input: &str) -> i32 {
-    let valves = parse_input(input);
+    solve_part1_with(input, &Params::default())
+}
+
+pub fn solve_part2(input: &str) -> i32 {
+    solve_part2_with(input, &Params::default())
+}
+
+pub fn solve_part1_with(input: &str, params: &Params) -> i32 {
+    try_solve_part1(input, params).unwrap()
+}
+
+pub fn solve_part2_with(input: &str, params: &Params) -> i32 {
+    try_solve_part2(input, params).unwrap()
+}
+
+pub fn try_solve_part1(input: &str, params: &Params) -> Result<i32, ConfigError> {
+    check_start(input, &params.start)?;
+    let valves = parse_input(input, &params.start);
     let mut cache = HashMap::new();
-    let remain = 30;
+    let remain = params.minutes;
 
     let init_snapshot = Snapshot {
-        pos: 0, // AA -> 0
+        pos: 0, // start -> 0
         opened: 0,
         remain,
     };
 
-    max_pressure(init_snapshot, &valves, &mut cache)
+    Ok(max_pressure(init_snapshot, &valves, &mut cache))
+}
+
+// the configured start has to be one of the input's valves
+fn check_start(input: &str, start: &str) -> Result<(), ConfigError> {
+    let prefix = format!("Valve {start} ");
+    if input.lines().any(|line| line.starts_with(&prefix)) {
+        Ok(())
+    } else {
+        Err(ConfigError::InvalidValue(
+            "day_16.start".to_string(),
+            start.to_string(),
+        ))
+    }
 }
 
 fn max_pressure(snapshot: Snapshot, valves: &[Valve], cache: &mut HashMap<Snapshot, i32>) -> i32 {
@@ -27,9 +100,9 @@ fn max_pressure(snapshot: Snapshot, valves: &[Valve], cache: &mut HashMap<Snapsh
     }
 
     let mut flow_rate = 0;
-    for idx in 0..valves.len() {
+    for (idx, valve) in valves.iter().enumerate() {
         if is_set(snapshot.opened, idx as i32) {
-            flow_rate += valves[idx].flow_rate;
+            flow_rate += valve.flow_rate;
         }
     }
 
@@ -60,10 +133,11 @@ fn max_pressure(snapshot: Snapshot, valves: &[Valve], cache: &mut HashMap<Snapsh
     pressure
 }
 
-pub fn solve_part2(input: &str) -> i32 {
-    let valves = parse_input(input);
+pub fn try_solve_part2(input: &str, params: &Params) -> Result<i32, ConfigError> {
+    check_start(input, &params.start)?;
+    let valves = parse_input(input, &params.start);
     let mut cache = BTreeMap::new();
-    let remain = 26;
+    let remain = params.minutes_with_elephant;
 
     let init_snapshot = Snapshot2 {
         pos: 0,
@@ -75,14 +149,14 @@ pub fn solve_part2(input: &str) -> i32 {
     let mut cur_max = 0;
     let max_pressure_per_min = valves.iter().map(|v| v.flow_rate).sum::<i32>();
 
-    max_pressure2(
+    Ok(max_pressure2(
         0,
         init_snapshot,
         &valves,
         &mut cache,
         &mut cur_max,
         max_pressure_per_min,
-    )
+    ))
 }
 
 fn max_pressure2(
@@ -106,9 +180,9 @@ fn max_pressure2(
     }
 
     let mut flow_rate = 0;
-    for idx in 0..valves.len() {
+    for (idx, valve) in valves.iter().enumerate() {
         if is_set(snapshot.opened, idx as i32) {
-            flow_rate += valves[idx].flow_rate;
+            flow_rate += valve.flow_rate;
         }
     }
 
@@ -203,7 +277,7 @@ fn max_pressure2(
     pressure
 }
 
-fn parse_input(input: &str) -> Vec<Valve> {
+fn parse_input(input: &str, start: &str) -> Vec<Valve> {
     struct RawValve {
         name: String,
         flow_rate: i32,
@@ -244,10 +318,14 @@ fn parse_input(input: &str) -> Vec<Valve> {
 
     // name -> index
     let mut name_to_index = HashMap::new();
-    name_to_index.insert("AA".to_string(), 0);
-
-    for (v, _) in &raw_valves {
-        if v == "AA" {
+    assert!(
+        raw_valves.contains_key(start),
+        "unknown start valve {start}"
+    );
+    name_to_index.insert(start.to_string(), 0);
+
+    for v in raw_valves.keys() {
+        if v == start {
             continue;
         }
 
@@ -296,7 +374,7 @@ fn is_set(value: u64, pos: i32) -> bool {
 }
 
 fn set(value: &mut u64, pos: i32) {
-    *value = *value | (1 << pos)
+    *value |= 1 << pos
 }
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Hash)]
@@ -324,7 +402,7 @@ Valve JJ has flow rate=21; tunnel leads to valve II"#;
 
     #[test]
     fn test_parse_sample() {
-        let valves = parse_input(SAMPLE_INPUT);
+        let valves = parse_input(SAMPLE_INPUT, "AA");
         assert_eq!(valves.len(), 10);
         assert_eq!(valves[0].flow_rate, 0);
     }
@@ -335,6 +413,27 @@ Valve JJ has flow rate=21; tunnel leads to valve II"#;
         assert_eq!(answer, 1651);
     }
 
+    #[test]
+    fn test_params() {
+        let config = Config::parse("day_16.minutes = 3\nday_16.start = DD").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        // open DD (20) in the first minute, then it flows for two
+        assert_eq!(solve_part1_with(SAMPLE_INPUT, &params), 40);
+
+        let valves = parse_input(SAMPLE_INPUT, "DD");
+        assert_eq!(valves[0].flow_rate, 20);
+
+        let config = Config::parse("day_16.start = ZZ").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        let err = ConfigError::InvalidValue("day_16.start".to_string(), "ZZ".to_string());
+        assert_eq!(try_solve_part1(SAMPLE_INPUT, &params), Err(err.clone()));
+        assert_eq!(try_solve_part2(SAMPLE_INPUT, &params), Err(err));
+
+        for bad in ["day_16.start = aa", "day_16.minutes = -1"] {
+            assert!(Params::from_config(&Config::parse(bad).unwrap()).is_err());
+        }
+    }
+
     #[test]
     #[ignore]
     fn test_part1() {
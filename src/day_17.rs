@@ -2,15 +2,67 @@
 #![allow(unused_variables)]
 #![allow(unused_mut)]
 
+use crate::{
+    config::{Config, ConfigError},
+    overflow::{self, OverflowError},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub part1_rocks: u64,
+    pub part2_rocks: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            part1_rocks: 2022,
+            part2_rocks: 1000000000000,
+        }
+    }
+}
+
+impl Params {
+    /// Every config key `from_config` reads.
+    pub const KEYS: &'static [&'static str] = &["day_17.part1_rocks", "day_17.part2_rocks"];
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let default = Params::default();
+        Ok(Params {
+            part1_rocks: config
+                .get_valid_or("day_17.part1_rocks", default.part1_rocks, |&n| n > 0)?,
+            part2_rocks: config
+                .get_valid_or("day_17.part2_rocks", default.part2_rocks, |&n| n > 0)?,
+        })
+    }
+}
+
 pub fn solve_part1(input: &str) -> u64 {
-    simulate(input, 2022)
+    solve_part1_with(input, &Params::default())
 }
 
 pub fn solve_part2(input: &str) -> u64 {
-    simulate2(input, 1000000000000_usize)
+    solve_part2_with(input, &Params::default())
 }
 
-fn simulate(input: &str, count: u64) -> u64 {
+pub fn solve_part1_with(input: &str, params: &Params) -> u64 {
+    try_solve_part1(input, params).unwrap()
+}
+
+pub fn solve_part2_with(input: &str, params: &Params) -> u64 {
+    try_solve_part2(input, params).unwrap()
+}
+
+pub fn try_solve_part1(input: &str, params: &Params) -> Result<u64, OverflowError> {
+    simulate(input, params.part1_rocks)
+}
+
+pub fn try_solve_part2(input: &str, params: &Params) -> Result<u64, OverflowError> {
+    simulate2(input, params.part2_rocks)
+}
+
+fn simulate(input: &str, count: u64) -> Result<u64, OverflowError> {
+    let count: usize = overflow::cast(count, 17, "rock count as usize")?;
     let jets = load_input(input);
     let rocks = [
         RockType::Horizontal,
@@ -24,7 +76,7 @@ fn simulate(input: &str, count: u64) -> u64 {
     for (idx, &rock_type) in rocks.iter().cycle().enumerate() {
         let mut rock = chamber.create_rock(rock_type);
 
-        while let Some(&dir) = jets.next() {
+        for &dir in jets.by_ref() {
             chamber.move_rock(&mut rock, dir);
             // chamber.print(Some(&rock));
             if !chamber.move_down_rock(&mut rock) {
@@ -33,8 +85,8 @@ fn simulate(input: &str, count: u64) -> u64 {
             }
         }
 
-        if idx + 1 == count as usize {
-            return chamber.height() as u64;
+        if idx + 1 == count {
+            return overflow::cast(chamber.height(), 17, "height as u64");
         }
     }
 
@@ -48,7 +100,7 @@ struct Pattern {
     spaces: Vec<[bool; CHAMBER_WIDTH as usize]>,
 }
 
-fn simulate2(input: &str, count: usize) -> u64 {
+fn simulate2(input: &str, count: usize) -> Result<u64, OverflowError> {
     let jets = load_input(input);
     let rocks = [
         RockType::Horizontal,
@@ -72,7 +124,7 @@ fn simulate2(input: &str, count: usize) -> u64 {
 
         let mut rock = chamber.create_rock(rock_type);
 
-        while let Some(&dir) = jets_it.next() {
+        for &dir in jets_it.by_ref() {
             jet_idx += 1;
 
             chamber.move_rock(&mut rock, dir);
@@ -86,7 +138,7 @@ fn simulate2(input: &str, count: usize) -> u64 {
         if !pattern_found && (idx - 1) % cycle == 0 {
             let cur_pattern = Pattern {
                 rock_idx: idx,
-                jet_idx: jet_idx,
+                jet_idx,
                 spaces: chamber.spaces.clone(),
             };
 
@@ -108,7 +160,9 @@ fn simulate2(input: &str, count: usize) -> u64 {
                         base, chamber.base, c, r
                     );
                     println!("prev height: {}, cur height: {}", height, chamber.height());
-                    chamber.base = *base + c * (chamber.base - *base);
+                    let skipped =
+                        overflow::mul(c, chamber.base - *base, 17, "skipped cycles height")?;
+                    chamber.base = overflow::add(*base, skipped, 17, "skipped cycles height")?;
 
                     idx = count - r;
                     pattern_found = true;
@@ -121,7 +175,7 @@ fn simulate2(input: &str, count: usize) -> u64 {
         }
 
         if idx == count {
-            return chamber.height() as u64;
+            return overflow::cast(chamber.height(), 17, "height as u64");
         }
     }
 
@@ -250,7 +304,7 @@ impl Chamber {
         // check enough height
         let height = rock.y + rock.rock_type.height();
         if (self.height() as u64) < height {
-            let need = height as usize - self.height() as usize;
+            let need = height as usize - self.height();
             let total = self.spaces.len() + need;
             self.spaces.resize(total, [false; CHAMBER_WIDTH as usize]);
         }
@@ -318,7 +372,7 @@ impl Chamber {
             }
         }
 
-        let base = smallest_y.max(0);
+        let base = smallest_y;
         self.spaces = self.spaces.split_off(base);
         self.base += base;
     }
@@ -331,7 +385,7 @@ impl Chamber {
             self.height()
         };
 
-        for y in (self.base..max_y as usize).rev() {
+        for y in (self.base..max_y).rev() {
             print!("[{:5}] |", y);
             for x in 0..CHAMBER_WIDTH {
                 if let Some(rock) = rock {
@@ -340,7 +394,7 @@ impl Chamber {
                         continue;
                     }
                 }
-                if let Some(row) = self.spaces.get(y as usize - self.base) {
+                if let Some(row) = self.spaces.get(y - self.base) {
                     if row[x as usize] {
                         print!("#");
                     } else {
@@ -353,7 +407,7 @@ impl Chamber {
             println!("|");
         }
         println!("[ base] +-------+");
-        println!("");
+        println!();
     }
 }
 
@@ -423,14 +477,14 @@ mod tests {
         let mut rock = chamber.create_rock(RockType::Square);
 
         for _ in 0..3 {
-            assert_eq!(chamber.move_rock(&mut rock, Dir::Right), true);
+            assert!(chamber.move_rock(&mut rock, Dir::Right));
         }
-        assert_eq!(chamber.move_rock(&mut rock, Dir::Right), false);
+        assert!(!chamber.move_rock(&mut rock, Dir::Right));
 
         for _ in 0..3 {
-            assert_eq!(chamber.move_down_rock(&mut rock), true);
+            assert!(chamber.move_down_rock(&mut rock));
         }
-        assert_eq!(chamber.move_down_rock(&mut rock), false);
+        assert!(!chamber.move_down_rock(&mut rock));
 
         chamber.land_rock(rock);
         assert_eq!(chamber.height(), 2);
@@ -439,9 +493,9 @@ mod tests {
         let mut rock = chamber.create_rock(RockType::Cross);
 
         for _ in 0..2 {
-            assert_eq!(chamber.move_rock(&mut rock, Dir::Left), true);
+            assert!(chamber.move_rock(&mut rock, Dir::Left));
         }
-        assert_eq!(chamber.move_rock(&mut rock, Dir::Left), false);
+        assert!(!chamber.move_rock(&mut rock, Dir::Left));
     }
 
     #[test]
@@ -470,9 +524,9 @@ mod tests {
         // chamber.print(Some(&rock));
         assert_eq!((rock.x, rock.y), (2, 7));
         for _ in 0..3 {
-            assert_eq!(chamber.move_down_rock(&mut rock), true);
+            assert!(chamber.move_down_rock(&mut rock));
         }
-        assert_eq!(chamber.move_down_rock(&mut rock), false);
+        assert!(!chamber.move_down_rock(&mut rock));
         // chamber.print(Some(&rock));
         chamber.land_rock(rock);
         assert_eq!(chamber.height(), 5);
@@ -503,4 +557,30 @@ mod tests {
         let answer = solve_part2(SAMPLE_INPUT);
         assert_eq!(answer, 1514285714288);
     }
+
+    #[test]
+    fn test_params() {
+        // part 2's cycle skipping agrees with the plain simulation
+        let config = Config::parse("day_17.part2_rocks = 2022").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        assert_eq!(solve_part2_with(SAMPLE_INPUT, &params), 3068);
+
+        let config = Config::parse("day_17.part1_rocks = 1").unwrap();
+        let params = Params::from_config(&config).unwrap();
+        assert_eq!(solve_part1_with(SAMPLE_INPUT, &params), 1);
+
+        for key in ["day_17.part1_rocks", "day_17.part2_rocks"] {
+            let config = Config::parse(&format!("{key} = 0")).unwrap();
+            assert!(Params::from_config(&config).is_err());
+        }
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        // skipping ahead usize::MAX rocks overflows the chamber base
+        let err = simulate2(SAMPLE_INPUT, usize::MAX).unwrap_err();
+        assert_eq!(err.day, 17);
+        assert_eq!(err.operation, "skipped cycles height");
+    }
 }
@@ -9,7 +9,7 @@ pub fn solve_part1(input: &str) -> u32 {
     let mut grid = vec![vec![vec![false; max_z + 1]; max_y + 1]; max_x + 1];
 
     for (x, y, z) in input {
-        grid[x as usize][y as usize][z as usize] = true;
+        grid[x][y][z] = true;
     }
 
     let mut count = 0;
@@ -83,9 +83,7 @@ fn map_inside(grid: Vec<Vec<Vec<bool>>>) -> Vec<Vec<Vec<Cube>>> {
         }
     }
 
-    while !stack.is_empty() {
-        let (x, y, z) = stack.pop().unwrap();
-
+    while let Some((x, y, z)) = stack.pop() {
         if visited[x][y][z] {
             continue;
         }
@@ -218,7 +216,7 @@ mod tests {
         let valves = parse_input(SAMPLE_INPUT);
         assert_eq!(valves.len(), 13);
         assert_eq!(valves[0], (2, 2, 2));
-        assert_eq!(valves[0], (1, 2, 2));
+        assert_eq!(valves[1], (1, 2, 2));
     }
 
     #[test]
@@ -0,0 +1,304 @@
+//! Inclusive integer ranges and sets of them.
+
+use num::PrimInt;
+
+/// The integers `start..=end`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        assert!(start <= end, "interval start is past its end");
+        Interval { start, end }
+    }
+
+    /// `None` when `start` is past `end`.
+    pub fn try_new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// Number of integers covered, `None` when that doesn't fit in `T` (the
+    /// full range of the type).
+    pub fn len(&self) -> Option<T> {
+        self.end.checked_sub(&self.start)?.checked_add(&T::one())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start.max(other.start) <= self.end.min(other.end)
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    // overlapping or directly next to each other, so the union is one interval
+    fn touches(&self, other: &Interval<T>) -> bool {
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        first
+            .end
+            .checked_add(&T::one())
+            .is_none_or(|after| after >= second.start)
+    }
+}
+
+/// Disjoint intervals kept sorted, with touching intervals merged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut intervals: Vec<_> = iter.into_iter().collect();
+        intervals.sort();
+
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if last.touches(&interval) => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let mut merged = interval;
+        let mut last = first;
+        while last < self.intervals.len() && self.intervals[last].touches(&merged) {
+            merged.start = merged.start.min(self.intervals[last].start);
+            merged.end = merged.end.max(self.intervals[last].end);
+            last += 1;
+        }
+        // the interval right before `first` may end just before `interval`
+        if first > 0 && self.intervals[first - 1].touches(&merged) {
+            merged.start = self.intervals[first - 1].start;
+            self.intervals.splice(first - 1..last, [merged]);
+        } else {
+            self.intervals.splice(first..last, [merged]);
+        }
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        *self = self.difference(&IntervalSet {
+            intervals: vec![interval],
+        });
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (&self.intervals[i], &other.intervals[j]);
+            if let Some(common) = a.intersection(b) {
+                intervals.push(common);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// Everything in `self` that isn't in `other`.
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let mut j = 0;
+        for interval in &self.intervals {
+            let mut start = interval.start;
+            let mut remaining = true;
+            while j < other.intervals.len() && other.intervals[j].start <= interval.end {
+                let cut = other.intervals[j];
+                if cut.end >= start {
+                    if cut.start > start {
+                        intervals.push(Interval::new(start, cut.start - T::one()));
+                    }
+                    if cut.end >= interval.end {
+                        remaining = false;
+                        break;
+                    }
+                    start = cut.end + T::one();
+                }
+                j += 1;
+            }
+            if remaining {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The parts of `bounds` not covered by the set.
+    pub fn gaps(&self, bounds: Interval<T>) -> IntervalSet<T> {
+        IntervalSet {
+            intervals: vec![bounds],
+        }
+        .difference(self)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end < value);
+        self.intervals.get(idx).is_some_and(|i| i.contains(value))
+    }
+
+    /// Whether every integer of `interval` is in the set.
+    pub fn covers(&self, interval: &Interval<T>) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end < interval.start);
+        self.intervals
+            .get(idx)
+            .is_some_and(|i| i.contains_interval(interval))
+    }
+
+    /// Number of integers in the set, `None` when that doesn't fit in `T`.
+    pub fn coverage(&self) -> Option<T> {
+        self.intervals
+            .iter()
+            .try_fold(T::zero(), |sum, interval| sum.checked_add(&interval.len()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    fn bounds(set: &IntervalSet<i32>) -> Vec<(i32, i32)> {
+        set.intervals()
+            .iter()
+            .map(|i| (i.start(), i.end()))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::new(2, 6);
+        let b = Interval::new(4, 8);
+        assert_eq!(a.len(), Some(5));
+        assert_eq!(Interval::new(u8::MIN, u8::MAX).len(), None);
+        assert_eq!(Interval::new(i8::MIN, 0).len(), None);
+        assert_eq!(Interval::new(i8::MIN, -2).len(), Some(127));
+        assert_eq!(Interval::try_new(3, 2), None);
+        assert_eq!(Interval::try_new(2, 2), Some(Interval::new(2, 2)));
+        assert!(a.contains(2) && a.contains(6) && !a.contains(7));
+        assert!(a.overlaps(&b));
+        assert_eq!(a.intersection(&b), Some(Interval::new(4, 6)));
+        assert_eq!(a.intersection(&Interval::new(7, 9)), None);
+        assert!(Interval::new(2, 8).contains_interval(&Interval::new(3, 7)));
+        assert!(!a.contains_interval(&b));
+        assert_eq!(Interval::new(u8::MIN, u8::MAX).end(), 255);
+    }
+
+    #[test]
+    fn test_merge() {
+        let merged = set(&[(5, 7), (1, 3), (4, 4), (10, 12), (11, 20)]);
+        assert_eq!(bounds(&merged), [(1, 7), (10, 20)]);
+
+        let mut set = set(&[(1, 2), (6, 8), (12, 14)]);
+        set.insert(Interval::new(3, 4));
+        assert_eq!(bounds(&set), [(1, 4), (6, 8), (12, 14)]);
+        set.insert(Interval::new(5, 12));
+        assert_eq!(bounds(&set), [(1, 14)]);
+        set.insert(Interval::new(i32::MAX - 1, i32::MAX));
+        assert_eq!(bounds(&set), [(1, 14), (i32::MAX - 1, i32::MAX)]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 22), (28, 40)]);
+        assert_eq!(bounds(&a.union(&b)), [(1, 40)]);
+        assert_eq!(bounds(&a.intersection(&b)), [(5, 10), (20, 22), (28, 30)]);
+        assert_eq!(bounds(&a.difference(&b)), [(1, 4), (23, 27)]);
+        assert_eq!(bounds(&b.difference(&a)), [(11, 19), (31, 40)]);
+
+        let mut c = a.clone();
+        c.remove(Interval::new(3, 25));
+        assert_eq!(bounds(&c), [(1, 2), (26, 30)]);
+    }
+
+    #[test]
+    fn test_queries() {
+        let a = set(&[(1, 10), (20, 30)]);
+        assert!(a.contains(20) && !a.contains(15));
+        assert!(a.covers(&Interval::new(2, 9)));
+        assert!(!a.covers(&Interval::new(9, 21)));
+        assert_eq!(a.coverage(), Some(21));
+        let full: IntervalSet<u8> = [Interval::new(0, 127), Interval::new(128, 255)]
+            .into_iter()
+            .collect();
+        assert_eq!(full.coverage(), None);
+        let almost: IntervalSet<u8> = [Interval::new(1, 127), Interval::new(129, 255)]
+            .into_iter()
+            .collect();
+        assert_eq!(almost.coverage(), Some(254));
+        assert_eq!(
+            bounds(&a.gaps(Interval::new(0, 40))),
+            [(0, 0), (11, 19), (31, 40)]
+        );
+        assert!(IntervalSet::<i32>::new().is_empty());
+    }
+}
@@ -0,0 +1,598 @@
+use std::{collections::BTreeMap, fmt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeaderboardError {
+    Json(usize, String), // byte offset, reason
+    MissingField(String),
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeaderboardError::Json(pos, reason) => write!(f, "invalid json at {pos}: {reason}"),
+            LeaderboardError::MissingField(field) => write!(f, "missing field: {field}"),
+        }
+    }
+}
+
+impl std::error::Error for LeaderboardError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32, // as exported, see `standings` for the recomputed one
+    // (day, part) -> get_star_ts
+    pub star_ts: BTreeMap<(u32, u32), u64>,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    /// Seconds between getting part 1 and part 2 of `day`, if both are done
+    /// and part 2 isn't timestamped before part 1.
+    pub fn delta(&self, day: u32) -> Option<u64> {
+        let part1 = self.star_ts.get(&(day, 1))?;
+        let part2 = self.star_ts.get(&(day, 2))?;
+        part2.checked_sub(*part1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    pub members: Vec<Member>, // sorted by id
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub rank: usize,
+    pub member_id: u64,
+    pub name: String,
+    pub score: u32,
+    pub stars: u32,
+}
+
+pub fn parse_leaderboard(input: &str) -> Result<Leaderboard, LeaderboardError> {
+    let root = Parser::new(input).parse_document()?;
+
+    let event = match root.get("event") {
+        Some(Json::String(s)) => s.clone(),
+        Some(Json::Number(n)) => (*n as u64).to_string(),
+        _ => String::new(),
+    };
+    let owner_id = root.get("owner_id").and_then(Json::as_u64).unwrap_or(0);
+
+    let Some(Json::Object(members_json)) = root.get("members") else {
+        return Err(LeaderboardError::MissingField("members".to_string()));
+    };
+
+    let mut members = Vec::new();
+    for (key, member) in members_json {
+        let id = member
+            .get("id")
+            .and_then(Json::as_u64)
+            .or_else(|| key.parse().ok())
+            .ok_or_else(|| LeaderboardError::MissingField(format!("members.{key}.id")))?;
+        let name = match member.get("name") {
+            Some(Json::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+        let stars = member.get("stars").and_then(Json::as_u64).unwrap_or(0) as u32;
+        let local_score = member
+            .get("local_score")
+            .and_then(Json::as_u64)
+            .unwrap_or(0) as u32;
+
+        let mut star_ts = BTreeMap::new();
+        if let Some(Json::Object(days)) = member.get("completion_day_level") {
+            for (day, parts) in days {
+                let Ok(day) = day.parse::<u32>() else {
+                    continue;
+                };
+                let Json::Object(parts) = parts else {
+                    continue;
+                };
+                for (part, level) in parts {
+                    let Ok(part) = part.parse::<u32>() else {
+                        continue;
+                    };
+                    let ts = level
+                        .get("get_star_ts")
+                        .and_then(Json::as_u64)
+                        .ok_or_else(|| {
+                            LeaderboardError::MissingField(format!(
+                                "members.{key}.completion_day_level.{day}.{part}.get_star_ts"
+                            ))
+                        })?;
+                    star_ts.insert((day, part), ts);
+                }
+            }
+        }
+
+        members.push(Member {
+            id,
+            name,
+            stars,
+            local_score,
+            star_ts,
+        });
+    }
+    members.sort_by_key(|member| member.id);
+
+    Ok(Leaderboard {
+        event,
+        owner_id,
+        members,
+    })
+}
+
+impl Leaderboard {
+    /// Local score: for every star, the first member to get it scores N points,
+    /// the second N - 1 and so on, where N is the number of members.
+    pub fn local_scores(&self) -> BTreeMap<u64, u32> {
+        let member_count = self.members.len() as u32;
+        let mut scores: BTreeMap<u64, u32> = self.members.iter().map(|m| (m.id, 0)).collect();
+
+        let mut stars: BTreeMap<(u32, u32), Vec<(u64, u64)>> = BTreeMap::new();
+        for member in &self.members {
+            for (&key, &ts) in &member.star_ts {
+                stars.entry(key).or_default().push((ts, member.id));
+            }
+        }
+
+        for solvers in stars.values_mut() {
+            solvers.sort();
+            for (rank, (_ts, id)) in solvers.iter().enumerate() {
+                *scores.get_mut(id).unwrap() += member_count - rank as u32;
+            }
+        }
+
+        scores
+    }
+
+    pub fn standings(&self) -> Vec<Standing> {
+        let scores = self.local_scores();
+        let mut standings: Vec<Standing> = self
+            .members
+            .iter()
+            .map(|member| Standing {
+                rank: 0,
+                member_id: member.id,
+                name: member.display_name(),
+                score: scores[&member.id],
+                stars: member.star_ts.len() as u32,
+            })
+            .collect();
+
+        standings.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.stars.cmp(&a.stars))
+                .then(a.member_id.cmp(&b.member_id))
+        });
+
+        // tied scores share a rank
+        for idx in 0..standings.len() {
+            standings[idx].rank = if idx > 0 && standings[idx - 1].score == standings[idx].score {
+                standings[idx - 1].rank
+            } else {
+                idx + 1
+            };
+        }
+
+        standings
+    }
+
+    /// (member id, day, seconds from part 1 to part 2) for every completed day.
+    pub fn deltas(&self) -> Vec<(u64, u32, u64)> {
+        let mut deltas = Vec::new();
+        for member in &self.members {
+            for &(day, part) in member.star_ts.keys() {
+                if part != 1 {
+                    continue;
+                }
+                if let Some(delta) = member.delta(day) {
+                    deltas.push((member.id, day, delta));
+                }
+            }
+        }
+        deltas
+    }
+
+    pub fn max_day(&self) -> u32 {
+        self.members
+            .iter()
+            .flat_map(|member| member.star_ts.keys().map(|&(day, _)| day))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+pub fn render_standings(leaderboard: &Leaderboard) -> String {
+    let standings = leaderboard.standings();
+    let name_width = standings
+        .iter()
+        .map(|s| s.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    let mut result = format!(
+        "{:>4}  {:<name_width$}  {:>5}  {:>5}\n",
+        "Rank", "Name", "Score", "Stars"
+    );
+    for standing in standings {
+        result += &format!(
+            "{:>4}  {:<name_width$}  {:>5}  {:>5}\n",
+            standing.rank, standing.name, standing.score, standing.stars
+        );
+    }
+    result
+}
+
+/// One row per member, one column per day with the part 1 -> part 2 time.
+pub fn render_deltas(leaderboard: &Leaderboard) -> String {
+    let max_day = leaderboard.max_day();
+    let name_width = leaderboard
+        .members
+        .iter()
+        .map(|m| m.display_name().chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    let mut result = format!("{:<name_width$}", "Name");
+    for day in 1..=max_day {
+        result += &format!("  {:>8}", format!("day {day}"));
+    }
+    result += "\n";
+
+    for member in &leaderboard.members {
+        result += &format!("{:<name_width$}", member.display_name());
+        for day in 1..=max_day {
+            let cell = match member.delta(day) {
+                Some(delta) => format_duration(delta),
+                None => "-".to_string(),
+            };
+            result += &format!("  {cell:>8}");
+        }
+        result += "\n";
+    }
+    result
+}
+
+// 3725 -> "1:02:05"
+fn format_duration(seconds: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(n) if *n >= 0.0 => Some(*n as u64),
+            Json::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser {
+            input: input.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn error(&self, reason: &str) -> LeaderboardError {
+        LeaderboardError::Json(self.pos, reason.to_string())
+    }
+
+    fn parse_document(&mut self) -> Result<Json, LeaderboardError> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos != self.input.len() {
+            return Err(self.error("trailing characters"));
+        }
+        Ok(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), LeaderboardError> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c as char)))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Json, LeaderboardError> {
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => Ok(Json::String(self.parse_string()?)),
+            Some(b't') => self.parse_literal("true", Json::Bool(true)),
+            Some(b'f') => self.parse_literal("false", Json::Bool(false)),
+            Some(b'n') => self.parse_literal("null", Json::Null),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Json) -> Result<Json, LeaderboardError> {
+        if self.input[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("invalid literal"))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Json, LeaderboardError> {
+        let start = self.pos;
+        while self.pos < self.input.len()
+            && matches!(
+                self.input[self.pos],
+                b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'
+            )
+        {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.input[start..self.pos]).unwrap();
+        text.parse()
+            .map(Json::Number)
+            .map_err(|_| self.error("invalid number"))
+    }
+
+    fn parse_string(&mut self) -> Result<String, LeaderboardError> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(&c) = self.input.get(self.pos) else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let Some(&escaped) = self.input.get(self.pos) else {
+                        return Err(self.error("unterminated escape"));
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        b'"' | b'\\' | b'/' => bytes.push(escaped),
+                        b'n' => bytes.push(b'\n'),
+                        b't' => bytes.push(b'\t'),
+                        b'r' => bytes.push(b'\r'),
+                        b'b' => bytes.push(0x08),
+                        b'f' => bytes.push(0x0c),
+                        b'u' => {
+                            let hex = self
+                                .input
+                                .get(self.pos..self.pos + 4)
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .ok_or_else(|| self.error("invalid unicode escape"))?;
+                            self.pos += 4;
+                            let c = char::from_u32(hex).unwrap_or(char::REPLACEMENT_CHARACTER);
+                            let mut buf = [0_u8; 4];
+                            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                _ => bytes.push(c),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid utf-8"))
+    }
+
+    fn parse_array(&mut self) -> Result<Json, LeaderboardError> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.parse_value()?);
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json, LeaderboardError> {
+        self.expect(b'{')?;
+        let mut fields = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(b':')?;
+            let value = self.parse_value()?;
+            fields.push((key, value));
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_INPUT: &str = r#"{
+  "owner_id": 100,
+  "event": "2022",
+  "members": {
+    "100": {
+      "id": 100, "name": "zombie", "stars": 4, "local_score": 11, "global_score": 0,
+      "last_star_ts": 1670000600,
+      "completion_day_level": {
+        "1": {"1": {"get_star_ts": 1669870900, "star_index": 1}, "2": {"get_star_ts": 1669871000, "star_index": 2}},
+        "2": {"1": {"get_star_ts": 1669957800, "star_index": 3}, "2": {"get_star_ts": 1669961525, "star_index": 4}}
+      }
+    },
+    "200": {
+      "id": 200, "name": null, "stars": 3, "local_score": 7, "global_score": 0,
+      "last_star_ts": 1669958000,
+      "completion_day_level": {
+        "1": {"1": {"get_star_ts": 1669870800, "star_index": 5}, "2": {"get_star_ts": 1669871200, "star_index": 6}},
+        "2": {"1": {"get_star_ts": 1669958000, "star_index": 7}}
+      }
+    },
+    "300": {
+      "id": 300, "name": "elf \"three\"", "stars": 0, "local_score": 0, "global_score": 0,
+      "last_star_ts": 0, "completion_day_level": {}
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_parse_sample() {
+        let leaderboard = parse_leaderboard(SAMPLE_INPUT).unwrap();
+        assert_eq!(leaderboard.event, "2022");
+        assert_eq!(leaderboard.owner_id, 100);
+        assert_eq!(leaderboard.members.len(), 3);
+
+        let anonymous = &leaderboard.members[1];
+        assert_eq!(anonymous.display_name(), "(anonymous user #200)");
+        assert_eq!(anonymous.star_ts.len(), 3);
+        assert_eq!(anonymous.star_ts[&(1, 2)], 1669871200);
+        assert_eq!(
+            leaderboard.members[2].name.as_deref(),
+            Some("elf \"three\"")
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(matches!(
+            parse_leaderboard(r#"{"members": {"1": }}"#),
+            Err(LeaderboardError::Json(_, _))
+        ));
+        assert_eq!(
+            parse_leaderboard(r#"{"event": "2022"}"#),
+            Err(LeaderboardError::MissingField("members".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_local_scores() {
+        let leaderboard = parse_leaderboard(SAMPLE_INPUT).unwrap();
+        let scores = leaderboard.local_scores();
+        // matches the exported local_score
+        assert_eq!(scores[&100], 11);
+        assert_eq!(scores[&200], 7);
+        assert_eq!(scores[&300], 0);
+    }
+
+    #[test]
+    fn test_standings() {
+        let leaderboard = parse_leaderboard(SAMPLE_INPUT).unwrap();
+        let standings = leaderboard.standings();
+        let order: Vec<_> = standings.iter().map(|s| (s.rank, s.member_id)).collect();
+        assert_eq!(order, [(1, 100), (2, 200), (3, 300)]);
+    }
+
+    #[test]
+    fn test_deltas() {
+        let leaderboard = parse_leaderboard(SAMPLE_INPUT).unwrap();
+        assert_eq!(
+            leaderboard.deltas(),
+            [(100, 1, 100), (100, 2, 3725), (200, 1, 400)]
+        );
+
+        // a hand-edited export with part 2 before part 1
+        let mut member = leaderboard.members[0].clone();
+        member.star_ts.insert((1, 2), 0);
+        assert_eq!(member.delta(1), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let leaderboard = parse_leaderboard(SAMPLE_INPUT).unwrap();
+        let standings = render_standings(&leaderboard);
+        let mut lines = standings.lines();
+        assert_eq!(
+            lines.next(),
+            Some("Rank  Name                   Score  Stars")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("   1  zombie                    11      4")
+        );
+
+        let deltas = render_deltas(&leaderboard);
+        assert_eq!(
+            deltas.lines().nth(1),
+            Some("zombie                  0:01:40   1:02:05")
+        );
+        assert_eq!(
+            deltas.lines().nth(3),
+            Some("elf \"three\"                   -         -")
+        );
+    }
+}
@@ -1,3 +1,5 @@
+pub mod client;
+pub mod config;
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
@@ -16,3 +18,8 @@ pub mod day_15;
 pub mod day_16;
 pub mod day_17;
 pub mod day_18;
+pub mod interval;
+pub mod leaderboard;
+pub mod overflow;
+pub mod registry;
+pub mod scaffold;
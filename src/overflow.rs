@@ -0,0 +1,138 @@
+//! Arithmetic helpers for the spots where solvers can overflow.
+//!
+//! With the `checked` feature every helper uses checked arithmetic and reports
+//! which day and operation overflowed. Without it they compile to the plain
+//! operators, so behaviour is the usual debug-panic / release-wrap.
+
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OverflowError {
+    pub day: u32,
+    pub operation: &'static str,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {}: overflow in {}", self.day, self.operation)
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+#[cfg(feature = "checked")]
+mod imp {
+    use super::OverflowError;
+    use num::{CheckedAdd, CheckedMul, CheckedSub};
+
+    pub fn add<T: CheckedAdd>(
+        a: T,
+        b: T,
+        day: u32,
+        operation: &'static str,
+    ) -> Result<T, OverflowError> {
+        a.checked_add(&b).ok_or(OverflowError { day, operation })
+    }
+
+    pub fn sub<T: CheckedSub>(
+        a: T,
+        b: T,
+        day: u32,
+        operation: &'static str,
+    ) -> Result<T, OverflowError> {
+        a.checked_sub(&b).ok_or(OverflowError { day, operation })
+    }
+
+    pub fn mul<T: CheckedMul>(
+        a: T,
+        b: T,
+        day: u32,
+        operation: &'static str,
+    ) -> Result<T, OverflowError> {
+        a.checked_mul(&b).ok_or(OverflowError { day, operation })
+    }
+
+    pub fn cast<T, U: TryFrom<T> + Copy + 'static>(
+        value: T,
+        day: u32,
+        operation: &'static str,
+    ) -> Result<U, OverflowError>
+    where
+        T: num::traits::AsPrimitive<U>,
+    {
+        U::try_from(value).map_err(|_| OverflowError { day, operation })
+    }
+}
+
+#[cfg(not(feature = "checked"))]
+mod imp {
+    use super::OverflowError;
+    use std::ops::{Add, Mul, Sub};
+
+    pub fn add<T: Add<Output = T>>(
+        a: T,
+        b: T,
+        _day: u32,
+        _operation: &'static str,
+    ) -> Result<T, OverflowError> {
+        Ok(a + b)
+    }
+
+    pub fn sub<T: Sub<Output = T>>(
+        a: T,
+        b: T,
+        _day: u32,
+        _operation: &'static str,
+    ) -> Result<T, OverflowError> {
+        Ok(a - b)
+    }
+
+    pub fn mul<T: Mul<Output = T>>(
+        a: T,
+        b: T,
+        _day: u32,
+        _operation: &'static str,
+    ) -> Result<T, OverflowError> {
+        Ok(a * b)
+    }
+
+    pub fn cast<T, U: TryFrom<T> + Copy + 'static>(
+        value: T,
+        _day: u32,
+        _operation: &'static str,
+    ) -> Result<U, OverflowError>
+    where
+        T: num::traits::AsPrimitive<U>,
+    {
+        Ok(value.as_())
+    }
+}
+
+pub use imp::{add, cast, mul, sub};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_range() {
+        assert_eq!(add(1_u32, 2, 0, "add"), Ok(3));
+        assert_eq!(sub(3_i32, 5, 0, "sub"), Ok(-2));
+        assert_eq!(mul(6_u64, 7, 0, "mul"), Ok(42));
+        assert_eq!(cast::<u64, usize>(42, 0, "cast"), Ok(42));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let err = OverflowError {
+            day: 11,
+            operation: "old * old",
+        };
+        assert_eq!(mul(u64::MAX, 2, 11, "old * old"), Err(err));
+        assert_eq!(err.to_string(), "day 11: overflow in old * old");
+        assert!(add(u32::MAX, 1, 7, "add").is_err());
+        assert!(sub(0_u32, 1, 7, "sub").is_err());
+        assert!(cast::<i64, u32>(-1, 17, "cast").is_err());
+    }
+}
@@ -0,0 +1,322 @@
+use std::fmt;
+
+use crate::{
+    config::{Config, ConfigError},
+    overflow::OverflowError,
+    *,
+};
+
+type Part = fn(&str, &Config) -> Result<String, RunError>;
+
+/// Why a part produced no answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    Config(ConfigError),
+    Overflow(OverflowError),
+    Input(String), // the day's own error for input it can't solve
+    NoAnswer,      // the input parsed but has no solution
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Config(err) => write!(f, "{err}"),
+            RunError::Overflow(err) => write!(f, "{err}"),
+            RunError::Input(err) => write!(f, "{err}"),
+            RunError::NoAnswer => write!(f, "no answer for this input"),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<ConfigError> for RunError {
+    fn from(err: ConfigError) -> Self {
+        RunError::Config(err)
+    }
+}
+
+impl From<OverflowError> for RunError {
+    fn from(err: OverflowError) -> Self {
+        RunError::Overflow(err)
+    }
+}
+
+macro_rules! input_error {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for RunError {
+                fn from(err: $ty) -> Self {
+                    RunError::Input(err.to_string())
+                }
+            }
+        )*
+    };
+}
+
+input_error!(
+    day_01::ParseError,
+    day_02::ParseError,
+    day_03::RucksackError,
+    day_03::GroupError,
+    day_04::ParseError,
+    day_05::ProcedureError
+);
+
+impl From<day_07::SolveError> for RunError {
+    fn from(err: day_07::SolveError) -> Self {
+        match err {
+            day_07::SolveError::Overflow(err) => RunError::Overflow(err),
+            err => RunError::Input(err.to_string()),
+        }
+    }
+}
+
+/// What a part may return: a plain answer, or a `Result` whose error the
+/// runner reports instead of panicking.
+pub trait Answer {
+    fn into_answer(self) -> Result<String, RunError>;
+}
+
+macro_rules! plain_answer {
+    ($($ty:ty),*) => {
+        $(
+            impl Answer for $ty {
+                fn into_answer(self) -> Result<String, RunError> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+plain_answer!(i32, i64, u32, u64, usize, String);
+
+impl<T: Answer, E: Into<RunError>> Answer for Result<T, E> {
+    fn into_answer(self) -> Result<String, RunError> {
+        self.map_err(Into::into)?.into_answer()
+    }
+}
+
+impl<T: Answer> Answer for Option<T> {
+    fn into_answer(self) -> Result<String, RunError> {
+        self.ok_or(RunError::NoAnswer)?.into_answer()
+    }
+}
+
+/// A day's two parts with their answers rendered as text, so the runner can treat
+/// every day the same regardless of the answer type.
+///
+/// Days with puzzle parameters read them from the config, the rest ignore it.
+#[derive(Debug, Copy, Clone)]
+pub struct Solution {
+    pub day: u32,
+    pub part1: Part,
+    pub part2: Part,
+    pub keys: &'static [&'static str], // config keys the day reads
+}
+
+impl Solution {
+    pub fn run(&self, part: u32, input: &str, config: &Config) -> Option<Result<String, RunError>> {
+        match part {
+            1 => Some((self.part1)(input, config)),
+            2 => Some((self.part2)(input, config)),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! solution {
+    ($day:expr, $part1:expr, $part2:expr) => {
+        Solution {
+            day: $day,
+            part1: |input: &str, _config: &Config| ($part1)(input).into_answer(),
+            part2: |input: &str, _config: &Config| ($part2)(input).into_answer(),
+            keys: &[],
+        }
+    };
+    ($day:expr, $params:ty, $part1:expr, $part2:expr) => {
+        Solution {
+            day: $day,
+            part1: |input: &str, config: &Config| {
+                let params = <$params>::from_config(config)?;
+                ($part1)(input, &params).into_answer()
+            },
+            part2: |input: &str, config: &Config| {
+                let params = <$params>::from_config(config)?;
+                ($part2)(input, &params).into_answer()
+            },
+            keys: <$params>::KEYS,
+        }
+    };
+}
+
+// one entry per line, `aoc new` inserts new days here
+pub const SOLUTIONS: &[Solution] = &[
+    solution!(1, day_01::try_most_calories, day_01::try_top_three_calories),
+    solution!(
+        2,
+        day_02::try_total_score_part1,
+        day_02::try_total_score_part2
+    ),
+    solution!(3, day_03::try_solve_part1, day_03::try_solve_part2),
+    solution!(4, day_04::try_solve_part1, day_04::try_solve_part2),
+    solution!(5, day_05::try_solve_part1, day_05::try_solve_part2),
+    solution!(6, day_06::solve_part1, day_06::solve_part2),
+    solution!(
+        7,
+        day_07::Params,
+        day_07::try_solve_part1,
+        day_07::try_solve_part2
+    ),
+    solution!(8, day_08::solve_part1, day_08::solve_part2),
+    solution!(9, day_09::solve_part1, day_09::solve_part2),
+    solution!(
+        10,
+        day_10::Params,
+        day_10::try_solve_part1,
+        |input, _params| day_10::solve_part2(input)
+    ),
+    solution!(
+        11,
+        day_11::Params,
+        day_11::try_solve_part1,
+        day_11::try_solve_part2
+    ),
+    solution!(12, day_12::solve_part1, day_12::solve_part2),
+    solution!(13, day_13::solve_part1, day_13::solve_part2),
+    solution!(14, day_14::solve_part1, day_14::solve_part2),
+    solution!(
+        15,
+        day_15::Params,
+        day_15::try_solve_part1,
+        day_15::try_solve_part2
+    ),
+    solution!(
+        16,
+        day_16::Params,
+        day_16::try_solve_part1,
+        day_16::try_solve_part2
+    ),
+    solution!(
+        17,
+        day_17::Params,
+        day_17::try_solve_part1,
+        day_17::try_solve_part2
+    ),
+    solution!(18, day_18::solve_part1, day_18::solve_part2),
+];
+
+pub fn find(day: u32) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|solution| solution.day == day)
+}
+
+/// Rejects keys no day reads, so a typo doesn't silently do nothing.
+pub fn check_config(config: &Config) -> Result<(), ConfigError> {
+    match config
+        .keys()
+        .find(|key| !SOLUTIONS.iter().any(|solution| solution.keys.contains(key)))
+    {
+        Some(key) => Err(ConfigError::UnknownKey(key.to_string())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let solution = find(1).unwrap();
+        let input = include_str!("../input/day_01.txt");
+        let config = Config::default();
+        assert_eq!(
+            solution.run(1, input, &config),
+            Some(Ok("74394".to_string()))
+        );
+        assert_eq!(solution.run(3, input, &config), None);
+        assert!(find(26).is_none());
+    }
+
+    #[test]
+    fn test_input_error_is_reported() {
+        let result = find(1).unwrap().run(1, "1000\nlots", &Config::default());
+        assert_eq!(
+            result,
+            Some(Err(RunError::Input(
+                "line 2: not a calorie count: `lots`".to_string()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_rucksack_errors_are_reported() {
+        let solution = find(3).unwrap();
+        let config = Config::default();
+        assert_eq!(
+            solution.run(1, "abcabd\nab-ab-", &config),
+            Some(Err(RunError::Input("line 2: invalid item '-'".to_string())))
+        );
+        assert_eq!(
+            solution.run(2, "abc\nabd", &config),
+            Some(Err(RunError::Input(
+                "2 rucksacks can't be split into groups of 3".to_string()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_no_answer_is_reported() {
+        let result = find(6)
+            .unwrap()
+            .run(2, "abcabcabcabcabc", &Config::default());
+        assert_eq!(result, Some(Err(RunError::NoAnswer)));
+    }
+
+    #[test]
+    fn test_params_from_config() {
+        let solution = find(11).unwrap();
+        let input = include_str!("../input/day_11.txt");
+        let config = Config::parse("day_11.part1_rounds = 20").unwrap();
+        assert_eq!(
+            solution.run(1, input, &config),
+            Some(Ok("88208".to_string()))
+        );
+
+        let config = Config::parse("day_11.part1_rounds = twenty").unwrap();
+        assert!(matches!(
+            solution.run(1, input, &config),
+            Some(Err(RunError::Config(ConfigError::InvalidValue(_, _))))
+        ));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow_is_reported() {
+        let input = format!("$ cd /\n$ ls\n{0} a\n{0} b", u64::MAX);
+        let result = find(7).unwrap().run(1, &input, &Config::default());
+        assert!(matches!(result, Some(Err(RunError::Overflow(_)))));
+    }
+
+    #[test]
+    fn test_check_config() {
+        let config = Config::parse("day_07.need_space = 1\nday_16.start = BB").unwrap();
+        assert_eq!(check_config(&config), Ok(()));
+
+        for key in ["day_07.need_spce", "day_01.top", "verbose"] {
+            let config = Config::parse(&format!("{key} = 1")).unwrap();
+            assert_eq!(
+                check_config(&config),
+                Err(ConfigError::UnknownKey(key.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_days_are_sorted() {
+        for pair in SOLUTIONS.windows(2) {
+            assert!(pair[0].day < pair[1].day);
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    Io(io::Error),
+    InvalidDay(u32),
+    AlreadyExists(PathBuf),
+    MissingMarker(PathBuf, &'static str),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::Io(err) => write!(f, "io error: {err}"),
+            ScaffoldError::InvalidDay(day) => write!(f, "day must be between 1 and 25: {day}"),
+            ScaffoldError::AlreadyExists(path) => {
+                write!(f, "refusing to overwrite {}", path.display())
+            }
+            ScaffoldError::MissingMarker(path, marker) => {
+                write!(f, "could not find `{marker}` in {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(err: io::Error) -> Self {
+        ScaffoldError::Io(err)
+    }
+}
+
+const REGISTRY_START: &str = "pub const SOLUTIONS: &[Solution] = &[";
+const REGISTRY_END: &str = "];";
+
+/// Creates `src/day_NN.rs`, an empty `input/day_NN.txt` and the `lib.rs` and
+/// registry entries under `root`, returning every file it wrote.
+///
+/// Nothing is written if the day module or its registry entry already exists.
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<PathBuf>, ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::InvalidDay(day));
+    }
+
+    let module = format!("day_{day:02}");
+    let module_path = root.join("src").join(format!("{module}.rs"));
+    let input_path = root.join("input").join(format!("{module}.txt"));
+    let lib_path = root.join("src").join("lib.rs");
+    let registry_path = root.join("src").join("registry.rs");
+
+    if module_path.exists() {
+        return Err(ScaffoldError::AlreadyExists(module_path));
+    }
+
+    // build every edit first so a failure leaves the tree untouched
+    let lib = insert_module(&fs::read_to_string(&lib_path)?, &module)
+        .ok_or_else(|| ScaffoldError::AlreadyExists(lib_path.clone()))?;
+    let registry = insert_registry_entry(&fs::read_to_string(&registry_path)?, day, &module)
+        .map_err(|marker| match marker {
+            Some(marker) => ScaffoldError::MissingMarker(registry_path.clone(), marker),
+            None => ScaffoldError::AlreadyExists(registry_path.clone()),
+        })?;
+
+    let mut written = Vec::new();
+
+    fs::write(&module_path, module_template(&module))?;
+    written.push(module_path);
+
+    if !input_path.exists() {
+        fs::create_dir_all(input_path.parent().unwrap())?;
+        fs::write(&input_path, "")?;
+        written.push(input_path);
+    }
+
+    fs::write(&lib_path, lib)?;
+    written.push(lib_path);
+
+    fs::write(&registry_path, registry)?;
+    written.push(registry_path);
+
+    Ok(written)
+}
+
+fn module_template(module: &str) -> String {
+    format!(
+        r##"// placeholder answers until solved, so `aoc run` works right away
+pub fn solve_part1(_input: &str) -> u32 {{
+    0
+}}
+
+pub fn solve_part2(_input: &str) -> u32 {{
+    0
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    const SAMPLE_INPUT: &str = r#""#;
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part1_sample() {{
+        let answer = solve_part1(SAMPLE_INPUT);
+        assert_eq!(answer, 0);
+    }}
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part1() {{
+        let input = include_str!("../input/{module}.txt");
+        let answer = solve_part1(input);
+        assert_eq!(answer, 0);
+    }}
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part2_sample() {{
+        let answer = solve_part2(SAMPLE_INPUT);
+        assert_eq!(answer, 0);
+    }}
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_part2() {{
+        let input = include_str!("../input/{module}.txt");
+        let answer = solve_part2(input);
+        assert_eq!(answer, 0);
+    }}
+}}
+"##
+    )
+}
+
+// keeps the `pub mod day_NN;` lines sorted, None if already declared
+fn insert_module(lib: &str, module: &str) -> Option<String> {
+    let declaration = format!("pub mod {module};");
+    let mut lines: Vec<&str> = lib.lines().collect();
+    if lines.contains(&declaration.as_str()) {
+        return None;
+    }
+
+    let idx = lines
+        .iter()
+        .position(|line| line.starts_with("pub mod ") && *line > declaration.as_str())
+        .unwrap_or(lines.len());
+    lines.insert(idx, &declaration);
+
+    Some(lines.join("\n") + "\n")
+}
+
+// Err(Some(marker)) if the SOLUTIONS list can't be found, Err(None) if the day is registered
+fn insert_registry_entry(
+    registry: &str,
+    day: u32,
+    module: &str,
+) -> Result<String, Option<&'static str>> {
+    let mut lines: Vec<String> = registry.lines().map(str::to_string).collect();
+    let start = lines
+        .iter()
+        .position(|line| line == REGISTRY_START)
+        .ok_or(Some(REGISTRY_START))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line == REGISTRY_END)
+            .ok_or(Some(REGISTRY_END))?;
+
+    let mut idx = end;
+    for line_idx in start + 1..end {
+        let Some(rest) = lines[line_idx].trim().strip_prefix("solution!(") else {
+            continue;
+        };
+        // rustfmt may put the day on the line after `solution!(`
+        let rest = if rest.is_empty() {
+            lines[line_idx + 1].trim()
+        } else {
+            rest
+        };
+        let Ok(entry_day) = rest.split(',').next().unwrap().trim().parse::<u32>() else {
+            continue;
+        };
+
+        if entry_day == day {
+            return Err(None);
+        }
+        if entry_day > day {
+            idx = line_idx;
+            break;
+        }
+    }
+
+    lines.insert(
+        idx,
+        format!("    solution!({day}, {module}::solve_part1, {module}::solve_part2),"),
+    );
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "pub mod day_01;\npub mod day_18;\npub mod registry;\n";
+    const REGISTRY: &str = r#"pub const SOLUTIONS: &[Solution] = &[
+    solution!(1, day_01::most_calories, day_01::top_three_calories),
+    solution!(
+        15,
+        |input| day_15::solve_part1(input, 2000000),
+        |input| day_15::solve_part2(input, 4000000)
+    ),
+    solution!(18, day_18::solve_part1, day_18::solve_part2),
+];
+"#;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("aoc_scaffold_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/registry.rs"), REGISTRY).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_insert_module() {
+        assert_eq!(
+            insert_module(LIB, "day_19").unwrap(),
+            "pub mod day_01;\npub mod day_18;\npub mod day_19;\npub mod registry;\n"
+        );
+        assert_eq!(
+            insert_module(LIB, "day_02").unwrap(),
+            "pub mod day_01;\npub mod day_02;\npub mod day_18;\npub mod registry;\n"
+        );
+        assert_eq!(insert_module(LIB, "day_18"), None);
+    }
+
+    #[test]
+    fn test_insert_registry_entry() {
+        let registry = insert_registry_entry(REGISTRY, 16, "day_16").unwrap();
+        let lines: Vec<&str> = registry.lines().collect();
+        assert_eq!(
+            lines[7],
+            "    solution!(16, day_16::solve_part1, day_16::solve_part2),"
+        );
+        assert_eq!(
+            lines[8],
+            "    solution!(18, day_18::solve_part1, day_18::solve_part2),"
+        );
+
+        let registry = insert_registry_entry(REGISTRY, 19, "day_19").unwrap();
+        assert!(registry
+            .ends_with("    solution!(19, day_19::solve_part1, day_19::solve_part2),\n];\n"));
+
+        assert_eq!(insert_registry_entry(REGISTRY, 15, "day_15"), Err(None));
+        assert_eq!(
+            insert_registry_entry("", 19, "day_19"),
+            Err(Some(REGISTRY_START))
+        );
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = temp_root("new_day");
+
+        let written = new_day(&root, 19).unwrap();
+        assert_eq!(written.len(), 4);
+
+        let module = fs::read_to_string(root.join("src/day_19.rs")).unwrap();
+        assert!(module.contains("pub fn solve_part1(_input: &str) -> u32 {\n    0\n}"));
+        assert!(!module.contains("todo!()"));
+        assert!(module.contains(r#"include_str!("../input/day_19.txt")"#));
+        assert_eq!(
+            fs::read_to_string(root.join("input/day_19.txt")).unwrap(),
+            ""
+        );
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("pub mod day_19;\n"));
+
+        // second run must not touch anything
+        assert!(matches!(
+            new_day(&root, 19),
+            Err(ScaffoldError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            new_day(&root, 26),
+            Err(ScaffoldError::InvalidDay(26))
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_new_day_keeps_existing_input() {
+        let root = temp_root("keep_input");
+        fs::create_dir_all(root.join("input")).unwrap();
+        fs::write(root.join("input/day_20.txt"), "fetched").unwrap();
+
+        let written = new_day(&root, 20).unwrap();
+        assert_eq!(written.len(), 3);
+        assert_eq!(
+            fs::read_to_string(root.join("input/day_20.txt")).unwrap(),
+            "fetched"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}